@@ -0,0 +1,147 @@
+use super::Cookie;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::str::Utf8Error;
+
+/// A cookie parsed by [`Cookie::parse_bytes`], which borrows the name and the value
+/// from an input that is not known to be UTF-8.
+///
+/// Same as for [`Cookie`], the spans are not compared when checking for equality.
+#[derive(Clone, Debug)]
+pub struct ByteCookie<'a> {
+    name: &'a [u8],
+    value: &'a [u8],
+    quoted: bool,
+    name_span: Range<usize>,
+    value_span: Range<usize>,
+}
+
+impl<'a> ByteCookie<'a> {
+    pub(crate) fn new(
+        name: &'a [u8],
+        value: &'a [u8],
+        quoted: bool,
+        name_span: Range<usize>,
+        value_span: Range<usize>,
+    ) -> ByteCookie<'a> {
+        ByteCookie {
+            name,
+            value,
+            quoted,
+            name_span,
+            value_span,
+        }
+    }
+
+    /// Gets the name of the cookie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// let parsed_cookies = Cookie::parse_bytes(b"name=value").unwrap();
+    /// assert_eq!(b"name", parsed_cookies[0].get_name());
+    /// ```
+    pub fn get_name(&self) -> &'a [u8] {
+        self.name
+    }
+
+    /// Gets the value of the cookie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// let parsed_cookies = Cookie::parse_bytes(b"name=value").unwrap();
+    /// assert_eq!(b"value", parsed_cookies[0].get_value());
+    /// ```
+    pub fn get_value(&self) -> &'a [u8] {
+        self.value
+    }
+
+    /// Gets the byte range of the name in the parsed input. See [`Cookie::name_span`].
+    pub fn name_span(&self) -> Range<usize> {
+        self.name_span.clone()
+    }
+
+    /// Gets the byte range of the value in the parsed input. See [`Cookie::value_span`].
+    pub fn value_span(&self) -> Range<usize> {
+        self.value_span.clone()
+    }
+
+    /// Whether the value was enclosed in double quotes when parsed.
+    pub fn is_quoted(&self) -> bool {
+        self.quoted
+    }
+
+    /// Converts the name and the value to a [`Cookie`], checking that they are valid UTF-8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// let parsed_cookies = Cookie::parse_bytes(b"name=value").unwrap();
+    /// let cookie = parsed_cookies[0].to_cookie().unwrap();
+    ///
+    /// assert_eq!("name", cookie.get_name());
+    /// assert_eq!("value", cookie.get_value());
+    /// ```
+    pub fn to_cookie(&self) -> Result<Cookie<'a>, Utf8Error> {
+        Ok(Cookie::with_quoted(
+            std::str::from_utf8(self.name)?,
+            std::str::from_utf8(self.value)?,
+            self.quoted,
+        )
+        .with_spans(self.name_span(), self.value_span()))
+    }
+}
+
+impl PartialEq for ByteCookie<'_> {
+    fn eq(&self, other: &ByteCookie<'_>) -> bool {
+        self.name == other.name && self.value == other.value && self.quoted == other.quoted
+    }
+}
+
+impl Eq for ByteCookie<'_> {}
+
+impl Hash for ByteCookie<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.value.hash(state);
+        self.quoted.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Cookie;
+
+    #[test]
+    fn parse_bytes() {
+        const COOKIE_BYTES: &[u8] = b"test1=0x1234; test2=\"quoted\"; =nokey";
+        let parsed_cookies = Cookie::parse_bytes(COOKIE_BYTES).unwrap();
+        assert_eq!(3, parsed_cookies.len());
+
+        assert_eq!(b"test1", parsed_cookies[0].get_name());
+        assert_eq!(b"0x1234", parsed_cookies[0].get_value());
+
+        assert_eq!(b"test2", parsed_cookies[1].get_name());
+        assert_eq!(b"quoted", parsed_cookies[1].get_value());
+        assert_eq!(
+            "test2=\"quoted\"",
+            parsed_cookies[1].to_cookie().unwrap().to_string()
+        );
+
+        assert_eq!(b"", parsed_cookies[2].get_name());
+        assert_eq!(b"nokey", parsed_cookies[2].get_value());
+    }
+
+    #[test]
+    fn non_ascii() {
+        assert!(Cookie::parse_bytes(b"a=\xc3\xa9").is_err());
+        assert!(Cookie::parse_bytes(b"\xff=1").is_err());
+    }
+}
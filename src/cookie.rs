@@ -1,21 +1,72 @@
-use super::{lalrpop_util, CookieLexer, CookieLexerError, CookieToken};
+use super::cookie_iter::ByteCookieIter;
+use super::{
+    cookie_serializer, lalrpop_util, ByteCookie, CookieIter, CookieLexer, CookieLexerError,
+    CookieToken, OwnedCookie,
+};
 use std::fmt::{Display, Error as FormatterError, Formatter};
-
-const BASIC_COOKIE_ERROR_DESCRIPTION: &'static str = "Cookie Parsing Error";
-const INTERNAL_ERROR_DESCRIPTION: &'static str = "Internal Error";
-const PARSE_ERROR_DESCRIPTION: &'static str = "Parse Error";
-
-lalrpop_mod!(cookie_grammar);
-
-#[derive(Debug)]
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+
+const BASIC_COOKIE_ERROR_DESCRIPTION: &str = "Cookie Parsing Error";
+const DUPLICATE_ERROR_DESCRIPTION: &str = "Duplicate Error";
+const INTERNAL_ERROR_DESCRIPTION: &str = "Internal Error";
+const NETSCAPE_ERROR_DESCRIPTION: &str = "Netscape Cookie File Error";
+const PARSE_ERROR_DESCRIPTION: &str = "Parse Error";
+const PREFIX_ERROR_DESCRIPTION: &str = "Prefix Error";
+const SERIALIZE_ERROR_DESCRIPTION: &str = "Serialize Error";
+#[cfg(feature = "signed")]
+const SIGNATURE_ERROR_DESCRIPTION: &str = "Signature Error";
+const STORAGE_ERROR_DESCRIPTION: &str = "Storage Error";
+
+lalrpop_mod!(
+    #[allow(clippy::all)]
+    cookie_grammar
+);
+
+/// A cookie which borrows its name and value, e.g. from a parsed `Cookie` header.
+///
+/// Two cookies are equal if their names, values and quoted flags are equal,
+/// regardless of where they were parsed from.
+///
+/// With the `serde` feature, deserializing borrows the name and the value from the input,
+/// so it fails if they contain escape sequences; use [`OwnedCookie`](crate::OwnedCookie) instead.
+/// The spans are neither serialized nor deserialized.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cookie<'a> {
     name: &'a str,
     value: &'a str,
+    #[cfg_attr(feature = "serde", serde(default))]
+    quoted: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    name_span: Option<Range<usize>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    value_span: Option<Range<usize>>,
 }
 
 impl<'a> Cookie<'a> {
+    /// Creates a cookie with the given name and value.
+    ///
+    /// The name and the value are not validated until the cookie is serialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// let cookie = Cookie::new("name", "value");
+    /// assert_eq!("name", cookie.get_name());
+    /// assert_eq!("value", cookie.get_value());
+    /// ```
+    pub fn new(name: &'a str, value: &'a str) -> Cookie<'a> {
+        Cookie::with_quoted(name, value, false)
+    }
+
     /// Parses an [RFC 6265](https://tools.ietf.org/html/rfc6265.html#section-4.2.1) compliant cookie string.
     ///
+    /// With the `handwritten-parser` feature, the input is parsed by the same single-pass parser
+    /// as [`iter`](Cookie::iter) instead of the LALRPOP grammar. Both give the same results.
+    ///
     /// # Examples
     ///
     /// ```
@@ -30,14 +81,76 @@ impl<'a> Cookie<'a> {
     /// assert_eq!("value2", parsed_cookies[1].get_value());
     /// ```
     pub fn parse(input: &'a str) -> Result<Vec<Cookie<'a>>, Error> {
-        Ok(cookie_grammar::CookiesParser::new()
+        #[cfg(feature = "handwritten-parser")]
+        {
+            Cookie::iter(input).collect()
+        }
+
+        #[cfg(not(feature = "handwritten-parser"))]
+        {
+            Cookie::parse_with_grammar(input)
+        }
+    }
+
+    /// Parses the input with the LALRPOP grammar in `cookie_grammar.lalrpop`.
+    #[cfg_attr(feature = "handwritten-parser", allow(dead_code))]
+    pub(crate) fn parse_with_grammar(input: &'a str) -> Result<Vec<Cookie<'a>>, Error> {
+        cookie_grammar::CookiesParser::new()
             .parse(CookieLexer::new(input))
-            .map_err(ParseError::from_lalrpop_parse_error_to_error)?
+            .map_err(|err| ParseError::from_lalrpop_parse_error_to_error(input, err))?
             .clone_to_vec()
             .iter()
             .rev()
             .map(|tok| tok.with_str(input))
-            .collect::<Result<Vec<Cookie>, Error>>()?)
+            .collect::<Result<Vec<Cookie>, Error>>()
+    }
+
+    /// Parses a cookie string given as bytes, such as the value of an HTTP header
+    /// which is not known to be UTF-8.
+    ///
+    /// As the grammar only allows ASCII characters, the input does not need to be validated first.
+    /// Gives the same results as [`parse`](Cookie::parse) for UTF-8 input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// let parsed_cookies = Cookie::parse_bytes(b"cookie1=value1; cookie2=value2").unwrap();
+    ///
+    /// assert_eq!(b"cookie1", parsed_cookies[0].get_name());
+    /// assert_eq!(b"value1", parsed_cookies[0].get_value());
+    ///
+    /// assert_eq!("cookie2", parsed_cookies[1].to_cookie().unwrap().get_name());
+    /// ```
+    pub fn parse_bytes(input: &'a [u8]) -> Result<Vec<ByteCookie<'a>>, Error> {
+        ByteCookieIter::new(input).collect()
+    }
+
+    /// Lazily parses an [RFC 6265](https://tools.ietf.org/html/rfc6265.html#section-4.2.1) compliant cookie string,
+    /// yielding the cookies in the order they appear in the input.
+    ///
+    /// This gives the same results as [`parse`](Cookie::parse), without allocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// let mut cookies = Cookie::iter("cookie1=value1; cookie2=value2");
+    ///
+    /// let cookie = cookies.next().unwrap().unwrap();
+    /// assert_eq!("cookie1", cookie.get_name());
+    /// assert_eq!("value1", cookie.get_value());
+    ///
+    /// let cookie = cookies.next().unwrap().unwrap();
+    /// assert_eq!("cookie2", cookie.get_name());
+    /// assert_eq!("value2", cookie.get_value());
+    ///
+    /// assert!(cookies.next().is_none());
+    /// ```
+    pub fn iter(input: &'a str) -> CookieIter<'a> {
+        CookieIter::new(input)
     }
 
     /// Gets the name of the cookie.
@@ -67,12 +180,210 @@ impl<'a> Cookie<'a> {
     pub fn get_value(&self) -> &'a str {
         self.value
     }
+
+    /// Serializes cookies into an [RFC 6265](https://tools.ietf.org/html/rfc6265.html#section-4.2.1) compliant cookie string,
+    /// i.e. the value of a `Cookie` request header.
+    ///
+    /// Values which were enclosed in double quotes when parsed are quoted again.
+    /// Returns an error if a name is not a valid token, or a value contains characters
+    /// which are not allowed in a cookie value.
+    ///
+    /// Empty names are allowed on purpose, so that nameless cookies such as `nokey`,
+    /// which [`Cookie::parse`] returns with an empty name, are sent back as `=nokey`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// let parsed_cookies = Cookie::parse("session=abc; _ga=GA1.2; theme=\"dark\"").unwrap();
+    /// let filtered_cookies = parsed_cookies
+    ///     .into_iter()
+    ///     .filter(|cookie| !cookie.get_name().starts_with("_ga"))
+    ///     .collect::<Vec<Cookie>>();
+    ///
+    /// assert_eq!(
+    ///     "session=abc; theme=\"dark\"",
+    ///     Cookie::serialize(&filtered_cookies).unwrap()
+    /// );
+    /// ```
+    pub fn serialize(cookies: &[Cookie]) -> Result<String, Error> {
+        cookie_serializer::serialize_cookies(cookies)
+    }
+
+    /// Copies the name and the value into an [`OwnedCookie`], which does not borrow the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, OwnedCookie};
+    ///
+    /// let owned_cookies = {
+    ///     let header = String::from("name=value");
+    ///     Cookie::parse(&header)
+    ///         .unwrap()
+    ///         .into_iter()
+    ///         .map(Cookie::into_owned)
+    ///         .collect::<Vec<OwnedCookie>>()
+    /// };
+    ///
+    /// assert_eq!("name", owned_cookies[0].get_name());
+    /// assert_eq!("value", owned_cookies[0].get_value());
+    /// ```
+    pub fn into_owned(self) -> OwnedCookie {
+        OwnedCookie::from(&self)
+    }
+
+    /// Copies the name and the value into an [`OwnedCookie`], keeping the borrowed cookie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// let parsed_cookies = Cookie::parse("name=\"value\"").unwrap();
+    /// let owned_cookie = parsed_cookies[0].to_owned_cookie();
+    ///
+    /// assert_eq!(parsed_cookies[0], owned_cookie);
+    /// assert!(owned_cookie.is_quoted());
+    /// ```
+    pub fn to_owned_cookie(&self) -> OwnedCookie {
+        OwnedCookie::from(self)
+    }
+
+    /// Gets the byte range of the name in the parsed input, or `None` if the cookie was not parsed.
+    ///
+    /// The range is empty for cookies without a name, and starts where the value would have.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// const INPUT: &str = "a=1; name=value";
+    ///
+    /// let parsed_cookies = Cookie::parse(INPUT).unwrap();
+    /// assert_eq!(Some(5..9), parsed_cookies[1].name_span());
+    /// assert_eq!(None, Cookie::new("name", "value").name_span());
+    /// ```
+    pub fn name_span(&self) -> Option<Range<usize>> {
+        self.name_span.clone()
+    }
+
+    /// Gets the byte range of the value in the parsed input, or `None` if the cookie was not parsed.
+    ///
+    /// The range does not include the double quotes of a quoted value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// const INPUT: &str = "session=abc; theme=\"dark\"";
+    ///
+    /// let mut redacted = String::from(INPUT);
+    /// for cookie in Cookie::parse(INPUT).unwrap() {
+    ///     let span = cookie.value_span().unwrap();
+    ///     redacted.replace_range(span.clone(), &"*".repeat(span.len()));
+    /// }
+    ///
+    /// assert_eq!("session=***; theme=\"****\"", redacted);
+    /// ```
+    pub fn value_span(&self) -> Option<Range<usize>> {
+        self.value_span.clone()
+    }
+
+    /// Whether the value was enclosed in double quotes when parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Cookie;
+    ///
+    /// let parsed_cookies = Cookie::parse("a=1; b=\"2\"").unwrap();
+    /// assert!(!parsed_cookies[0].is_quoted());
+    /// assert!(parsed_cookies[1].is_quoted());
+    /// assert_eq!("2", parsed_cookies[1].get_value());
+    /// ```
+    pub fn is_quoted(&self) -> bool {
+        self.quoted
+    }
+
+    pub(crate) fn with_quoted(name: &'a str, value: &'a str, quoted: bool) -> Cookie<'a> {
+        Cookie {
+            name,
+            value,
+            quoted,
+            name_span: None,
+            value_span: None,
+        }
+    }
+
+    pub(crate) fn with_spans(
+        mut self,
+        name_span: Range<usize>,
+        value_span: Range<usize>,
+    ) -> Cookie<'a> {
+        self.name_span = Some(name_span);
+        self.value_span = Some(value_span);
+        self
+    }
+}
+
+impl PartialEq for Cookie<'_> {
+    fn eq(&self, other: &Cookie<'_>) -> bool {
+        self.name == other.name && self.value == other.value && self.quoted == other.quoted
+    }
 }
 
+impl Eq for Cookie<'_> {}
+
+impl Hash for Cookie<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.value.hash(state);
+        self.quoted.hash(state);
+    }
+}
+
+/// Formats the cookie as a `name=value` pair, with the value enclosed in double quotes
+/// if it was quoted when parsed. The name and the value are not validated.
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::Cookie;
+///
+/// let parsed_cookies = Cookie::parse("a=1; b=\"2\"").unwrap();
+/// assert_eq!("a=1", parsed_cookies[0].to_string());
+/// assert_eq!("b=\"2\"", parsed_cookies[1].to_string());
+/// ```
+impl Display for Cookie<'_> {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        if self.quoted {
+            write!(f, "{}=\"{}\"", self.name, self.value)
+        } else {
+            write!(f, "{}={}", self.name, self.value)
+        }
+    }
+}
+
+/// An error returned by this crate.
+///
+/// New variants may be added in minor releases, and some variants only exist with the features
+/// which produce them.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     InternalError(InternalError),
     ParseError(ParseError),
+    #[cfg(feature = "signed")]
+    SignatureError(SignatureError),
+    SerializeError(SerializeError),
+    StorageError(StorageError),
+    PrefixError(PrefixError),
+    DuplicateError(DuplicateError),
+    NetscapeError(NetscapeError),
 }
 
 impl Display for Error {
@@ -82,6 +393,13 @@ impl Display for Error {
         match self {
             Error::InternalError(err) => err.fmt(f),
             Error::ParseError(err) => err.fmt(f),
+            #[cfg(feature = "signed")]
+            Error::SignatureError(err) => err.fmt(f),
+            Error::SerializeError(err) => err.fmt(f),
+            Error::StorageError(err) => err.fmt(f),
+            Error::PrefixError(err) => err.fmt(f),
+            Error::DuplicateError(err) => err.fmt(f),
+            Error::NetscapeError(err) => err.fmt(f),
         }
     }
 }
@@ -99,6 +417,13 @@ impl std::error::Error for Error {
         match self {
             Error::InternalError(err) => Some(err),
             Error::ParseError(err) => Some(err),
+            #[cfg(feature = "signed")]
+            Error::SignatureError(err) => Some(err),
+            Error::SerializeError(err) => Some(err),
+            Error::StorageError(err) => Some(err),
+            Error::PrefixError(err) => Some(err),
+            Error::DuplicateError(err) => Some(err),
+            Error::NetscapeError(err) => Some(err),
         }
     }
 }
@@ -107,7 +432,7 @@ impl std::error::Error for Error {
 pub struct InternalError(InternalErrorKind);
 
 impl InternalError {
-    pub(crate) fn to_error(self) -> Error {
+    pub(crate) fn into_error(self) -> Error {
         Error::InternalError(self)
     }
 }
@@ -141,24 +466,181 @@ type LalrpopError = lalrpop_util::ParseError<usize, CookieToken, CookieLexerErro
 
 #[derive(Debug)]
 pub struct ParseError {
-    lalrpop_error: LalrpopError,
+    kind: ParseErrorKind,
+    offset: usize,
+    expected: Vec<&'static str>,
 }
 
 impl ParseError {
-    pub(crate) fn from_lalrpop_parse_error_to_error(src: LalrpopError) -> Error {
-        ParseError { lalrpop_error: src }.to_error()
+    /// Creates an error for the input at `offset`, which is the length of the input
+    /// if the end of the input was reached unexpectedly.
+    pub(crate) fn new(input: &[u8], offset: usize, expected: Vec<&'static str>) -> ParseError {
+        ParseError {
+            kind: ParseErrorKind::classify(input, offset),
+            offset,
+            expected,
+        }
+    }
+
+    pub(crate) fn with_kind(
+        kind: ParseErrorKind,
+        offset: usize,
+        expected: Vec<&'static str>,
+    ) -> ParseError {
+        ParseError {
+            kind,
+            offset,
+            expected,
+        }
+    }
+
+    pub(crate) fn from_lalrpop_parse_error_to_error(input: &str, src: LalrpopError) -> Error {
+        let (offset, expected) = match src {
+            lalrpop_util::ParseError::InvalidToken { location } => (location, Vec::new()),
+            lalrpop_util::ParseError::UnrecognizedEof { location, expected } => {
+                (location, expected)
+            }
+            lalrpop_util::ParseError::UnrecognizedToken {
+                token: (location, _, _),
+                expected,
+            } => (location, expected),
+            lalrpop_util::ParseError::ExtraToken {
+                token: (location, _, _),
+            } => (location, Vec::new()),
+            lalrpop_util::ParseError::User { error } => (error.offset(), Vec::new()),
+        };
+
+        let mut expected_descriptions = Vec::<&'static str>::new();
+        for terminal in expected.iter() {
+            let description = match terminal.as_str() {
+                "\"cookie_octets\"" | "\"token_or_cookie_octets\"" => "cookie-octet",
+                "\"av_octets\"" => "av-octet",
+                "\"=\"" => "\"=\"",
+                "\";\"" => "\";\"",
+                "\"\\\"\"" => "DQUOTE",
+                "\" \"" => "SP",
+                "\"ws\"" => "HTAB",
+                _ => continue,
+            };
+            if !expected_descriptions.contains(&description) {
+                expected_descriptions.push(description);
+            }
+        }
+
+        ParseError::new(input.as_bytes(), offset, expected_descriptions).into_error()
     }
 
-    fn to_error(self) -> Error {
+    pub(crate) fn into_error(self) -> Error {
         Error::ParseError(self)
     }
+
+    /// Gets the reason why the input could not be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, Error, ParseErrorKind};
+    ///
+    /// match Cookie::parse("a=b; c=\"d") {
+    ///     Err(Error::ParseError(err)) => assert_eq!(ParseErrorKind::UnterminatedQuote, err.kind()),
+    ///     _ => panic!("the input should have been rejected"),
+    /// }
+    /// ```
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Gets the byte offset in the input where the error was found.
+    /// It is the length of the input if the end of the input was reached unexpectedly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, Error};
+    ///
+    /// match Cookie::parse("a=b c") {
+    ///     Err(Error::ParseError(err)) => assert_eq!(4, err.offset()),
+    ///     _ => panic!("the input should have been rejected"),
+    /// }
+    /// ```
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Gets the descriptions of what would have been accepted at [`offset`](ParseError::offset),
+    /// using the names of the grammar in [RFC 6265](https://tools.ietf.org/html/rfc6265.html#section-4.2.1),
+    /// such as `cookie-octet`, `DQUOTE` or `";"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, Error};
+    ///
+    /// match Cookie::parse("a=\"b") {
+    ///     Err(Error::ParseError(err)) => assert!(err.expected().contains(&"DQUOTE")),
+    ///     _ => panic!("the input should have been rejected"),
+    /// }
+    /// ```
+    pub fn expected(&self) -> &[&'static str] {
+        &self.expected
+    }
+
+    /// Renders the input with a caret pointing at the column where the error was found,
+    /// followed by a description of the error.
+    ///
+    /// `input` must be the string which was parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, Error};
+    ///
+    /// const INPUT: &str = "a=1; b=2,3";
+    ///
+    /// match Cookie::parse(INPUT) {
+    ///     Err(Error::ParseError(err)) => assert_eq!(
+    ///         "a=1; b=2,3\n        ^ unexpected character ','",
+    ///         err.render(INPUT)
+    ///     ),
+    ///     _ => panic!("the input should have been rejected"),
+    /// }
+    /// ```
+    pub fn render(&self, input: &str) -> String {
+        let mut output = String::with_capacity(input.len() * 2 + 32);
+        output.push_str(input);
+        output.push('\n');
+
+        let prefix = input.get(..self.offset).unwrap_or(input).chars().map(|c| {
+            if c == '\t' {
+                '\t'
+            } else {
+                ' '
+            }
+        });
+        output.extend(prefix);
+        output.push_str("^ ");
+        output.push_str(&self.kind.to_string());
+
+        output
+    }
 }
 
 impl Display for ParseError {
     fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
         f.write_str(PARSE_ERROR_DESCRIPTION)?;
         f.write_str(": ")?;
-        self.lalrpop_error.fmt(f)
+        f.write_fmt(format_args!("{} at offset {}", self.kind, self.offset))?;
+
+        for (idx, expected) in self.expected.iter().enumerate() {
+            f.write_str(match idx {
+                0 => ", expected ",
+                idx if idx + 1 == self.expected.len() => " or ",
+                _ => ", ",
+            })?;
+            f.write_str(expected)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -168,14 +650,437 @@ impl std::error::Error for ParseError {
     }
 
     fn cause(&self) -> Option<&dyn std::error::Error> {
-        Some(&self.lalrpop_error)
+        None
     }
 
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
-        Some(&self.lalrpop_error)
+        None
     }
 }
 
+/// The reason why a cookie string could not be parsed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// A character which is not allowed at its position, such as a space within a value.
+    UnexpectedCharacter(char),
+    /// The input ended in the middle of a cookie.
+    UnexpectedEndOfInput,
+    /// A value starts with a double quote, but does not end with one.
+    UnterminatedQuote,
+    /// There is nothing but whitespace between two separators, or after the last one,
+    /// or the input is empty. In lenient parsing, a pair which has neither a name nor a value,
+    /// such as `=`, is also empty.
+    EmptyCookiePair,
+}
+
+impl ParseErrorKind {
+    fn classify(input: &[u8], offset: usize) -> ParseErrorKind {
+        let pair_start = input[..offset]
+            .iter()
+            .rposition(|b| *b == b';')
+            .map_or(0, |idx| idx + 1);
+        let pair_end = input[pair_start..]
+            .iter()
+            .position(|b| *b == b';')
+            .map_or(input.len(), |len| pair_start + len);
+
+        let quotes_before = input[pair_start..offset]
+            .iter()
+            .filter(|b| **b == b'"')
+            .count();
+
+        if input[pair_start..pair_end]
+            .iter()
+            .all(|b| CookieLexer::is_whitespace_char(char::from(*b)))
+        {
+            ParseErrorKind::EmptyCookiePair
+        } else if quotes_before % 2 == 1 && !input[offset..pair_end].contains(&b'"') {
+            ParseErrorKind::UnterminatedQuote
+        } else {
+            match input[offset..].utf8_chunks().next() {
+                None => ParseErrorKind::UnexpectedEndOfInput,
+                Some(chunk) => ParseErrorKind::UnexpectedCharacter(
+                    chunk
+                        .valid()
+                        .chars()
+                        .next()
+                        .unwrap_or(char::REPLACEMENT_CHARACTER),
+                ),
+            }
+        }
+    }
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        match self {
+            ParseErrorKind::UnexpectedCharacter(c) => {
+                f.write_fmt(format_args!("unexpected character {:?}", c))
+            }
+            ParseErrorKind::UnexpectedEndOfInput => f.write_str("unexpected end of input"),
+            ParseErrorKind::UnterminatedQuote => f.write_str("unterminated quote"),
+            ParseErrorKind::EmptyCookiePair => f.write_str("empty cookie pair"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct SerializeError(SerializeErrorKind);
+
+impl SerializeError {
+    pub(crate) fn new(kind: SerializeErrorKind) -> SerializeError {
+        SerializeError(kind)
+    }
+
+    pub(crate) fn into_error(self) -> Error {
+        Error::SerializeError(self)
+    }
+
+    /// Gets the part of the cookie which could not be serialized.
+    pub fn kind(&self) -> SerializeErrorKind {
+        self.0
+    }
+}
+
+impl Display for SerializeError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        f.write_str(SERIALIZE_ERROR_DESCRIPTION)?;
+        f.write_str(": ")?;
+        f.write_str(match self.0 {
+            SerializeErrorKind::Name => "invalid cookie name",
+            SerializeErrorKind::Value => "invalid cookie value",
+            SerializeErrorKind::AttributeValue => "invalid attribute value",
+            SerializeErrorKind::Expires => "expiry date out of range",
+        })
+    }
+}
+
+impl std::error::Error for SerializeError {
+    fn description(&self) -> &str {
+        SERIALIZE_ERROR_DESCRIPTION
+    }
+
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        None
+    }
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+/// The part of a cookie which could not be serialized.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SerializeErrorKind {
+    /// The name is not a token, or the name of a `Set-Cookie` header is empty.
+    Name,
+    /// The value contains characters which are not allowed in a cookie value.
+    Value,
+    /// The value of an attribute contains control characters or `;`.
+    AttributeValue,
+    /// The expiry date cannot be represented as an HTTP date.
+    Expires,
+}
+
+#[derive(Debug)]
+pub struct StorageError(StorageErrorKind);
+
+impl StorageError {
+    pub(crate) fn new(kind: StorageErrorKind) -> StorageError {
+        StorageError(kind)
+    }
+
+    pub(crate) fn into_error(self) -> Error {
+        Error::StorageError(self)
+    }
+
+    /// Gets the reason why the cookie was not stored.
+    pub fn kind(&self) -> StorageErrorKind {
+        self.0
+    }
+}
+
+impl Display for StorageError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        f.write_str(STORAGE_ERROR_DESCRIPTION)?;
+        f.write_str(": ")?;
+        f.write_str(match self.0 {
+            StorageErrorKind::InvalidRequestUrl => "invalid request URL",
+            StorageErrorKind::DomainMismatch => {
+                "the request host does not domain-match the Domain attribute"
+            }
+            StorageErrorKind::InvalidNameOrValue => {
+                "the name or the value cannot be sent in a Cookie header"
+            }
+            StorageErrorKind::PublicSuffix => "the Domain attribute is a public suffix",
+        })
+    }
+}
+
+impl std::error::Error for StorageError {
+    fn description(&self) -> &str {
+        STORAGE_ERROR_DESCRIPTION
+    }
+
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        None
+    }
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+/// The reason why a cookie jar did not store or look up a cookie.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StorageErrorKind {
+    /// The request URL is not an absolute URL with a host.
+    InvalidRequestUrl,
+    /// The `Domain` attribute does not match the host of the request URL.
+    DomainMismatch,
+    /// The name is not a token, or the value contains characters which are not allowed
+    /// in a `Cookie` header, so the cookie could not be sent back.
+    InvalidNameOrValue,
+    /// The `Domain` attribute is a public suffix which is different from the host
+    /// of the request URL.
+    PublicSuffix,
+}
+
+#[derive(Debug)]
+pub struct PrefixError(PrefixErrorKind);
+
+impl PrefixError {
+    pub(crate) fn new(kind: PrefixErrorKind) -> PrefixError {
+        PrefixError(kind)
+    }
+
+    pub(crate) fn into_error(self) -> Error {
+        Error::PrefixError(self)
+    }
+
+    /// Gets the requirement of the cookie name prefix which was not met.
+    pub fn kind(&self) -> PrefixErrorKind {
+        self.0
+    }
+}
+
+impl Display for PrefixError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        f.write_str(PREFIX_ERROR_DESCRIPTION)?;
+        f.write_str(": ")?;
+        f.write_str(match self.0 {
+            PrefixErrorKind::SecureRequired => "the Secure attribute is required",
+            PrefixErrorKind::SecureRequestUrlRequired => "the request URL must be secure",
+            PrefixErrorKind::DomainNotAllowed => "the Domain attribute is not allowed",
+            PrefixErrorKind::RootPathRequired => "the Path attribute must be /",
+        })
+    }
+}
+
+impl std::error::Error for PrefixError {
+    fn description(&self) -> &str {
+        PREFIX_ERROR_DESCRIPTION
+    }
+
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        None
+    }
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+/// The reason why a cookie with a `__Secure-` or `__Host-` name prefix was rejected, as described in
+/// [RFC 6265bis](https://datatracker.ietf.org/doc/html/draft-ietf-httpbis-rfc6265bis#section-4.1.3).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrefixErrorKind {
+    /// The cookie does not have the `Secure` attribute.
+    SecureRequired,
+    /// The cookie was received in the response to a request with an insecure scheme.
+    SecureRequestUrlRequired,
+    /// The cookie has a `__Host-` prefix and a `Domain` attribute.
+    DomainNotAllowed,
+    /// The cookie has a `__Host-` prefix, and its `Path` attribute is missing or is not `/`.
+    RootPathRequired,
+}
+
+#[cfg(feature = "signed")]
+#[derive(Debug)]
+pub struct SignatureError(SignatureErrorKind);
+
+#[cfg(feature = "signed")]
+impl SignatureError {
+    pub(crate) fn new(kind: SignatureErrorKind) -> SignatureError {
+        SignatureError(kind)
+    }
+
+    pub(crate) fn into_error(self) -> Error {
+        Error::SignatureError(self)
+    }
+
+    /// Gets the reason why the signed or encrypted cookie could not be verified.
+    pub fn kind(&self) -> SignatureErrorKind {
+        self.0
+    }
+}
+
+#[cfg(feature = "signed")]
+impl Display for SignatureError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        f.write_str(SIGNATURE_ERROR_DESCRIPTION)?;
+        f.write_str(": ")?;
+        f.write_str(match self.0 {
+            SignatureErrorKind::Malformed => "the value is not signed or encrypted",
+            SignatureErrorKind::Mismatch => "the signature does not match any key",
+        })
+    }
+}
+
+#[cfg(feature = "signed")]
+impl std::error::Error for SignatureError {
+    fn description(&self) -> &str {
+        SIGNATURE_ERROR_DESCRIPTION
+    }
+
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        None
+    }
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+/// The reason why a signed or encrypted cookie was rejected.
+#[cfg(feature = "signed")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureErrorKind {
+    /// The value is too short, or is not valid base64.
+    Malformed,
+    /// The value was not signed or encrypted with any of the keys, or the name or the value was changed.
+    Mismatch,
+}
+
+#[derive(Debug)]
+pub struct DuplicateError(String);
+
+impl DuplicateError {
+    pub(crate) fn new(name: &str) -> DuplicateError {
+        DuplicateError(name.to_string())
+    }
+
+    pub(crate) fn into_error(self) -> Error {
+        Error::DuplicateError(self)
+    }
+
+    /// Gets the name of the cookie which appears more than once.
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for DuplicateError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(
+            f,
+            "{}: the cookie {:?} appears more than once",
+            DUPLICATE_ERROR_DESCRIPTION, self.0
+        )
+    }
+}
+
+impl std::error::Error for DuplicateError {
+    fn description(&self) -> &str {
+        DUPLICATE_ERROR_DESCRIPTION
+    }
+
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        None
+    }
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+#[derive(Debug)]
+pub struct NetscapeError {
+    line: usize,
+    kind: NetscapeErrorKind,
+}
+
+impl NetscapeError {
+    pub(crate) fn new(line: usize, kind: NetscapeErrorKind) -> NetscapeError {
+        NetscapeError { line, kind }
+    }
+
+    pub(crate) fn into_error(self) -> Error {
+        Error::NetscapeError(self)
+    }
+
+    /// Gets the number of the line which could not be read, starting from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Gets the reason why the line could not be read.
+    pub fn kind(&self) -> NetscapeErrorKind {
+        self.kind
+    }
+}
+
+impl Display for NetscapeError {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        write!(
+            f,
+            "{}: line {}: {}",
+            NETSCAPE_ERROR_DESCRIPTION,
+            self.line,
+            match self.kind {
+                NetscapeErrorKind::FieldCount => "expected 7 tab-separated fields",
+                NetscapeErrorKind::EmptyDomain => "the domain is empty",
+                NetscapeErrorKind::InvalidPath => "the path does not start with '/'",
+                NetscapeErrorKind::InvalidFlag => "expected TRUE or FALSE",
+                NetscapeErrorKind::InvalidExpiry => "the expiry is not a non-negative integer",
+                NetscapeErrorKind::InvalidNameOrValue => "the name or the value is not valid",
+            }
+        )
+    }
+}
+
+impl std::error::Error for NetscapeError {
+    fn description(&self) -> &str {
+        NETSCAPE_ERROR_DESCRIPTION
+    }
+
+    fn cause(&self) -> Option<&dyn std::error::Error> {
+        None
+    }
+
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        None
+    }
+}
+
+/// The reason why a line of a Netscape `cookies.txt` file could not be read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NetscapeErrorKind {
+    /// The line does not have 7 tab-separated fields.
+    FieldCount,
+    /// The domain field is empty.
+    EmptyDomain,
+    /// The path field does not start with `/`.
+    InvalidPath,
+    /// The include-subdomains or the secure field is neither `TRUE` nor `FALSE`.
+    InvalidFlag,
+    /// The expiry field is not a non-negative number of seconds since the Unix epoch.
+    InvalidExpiry,
+    /// The name is not a token, or the value contains characters which are not allowed
+    /// in a `Cookie` header.
+    InvalidNameOrValue,
+}
+
 mod terminals {
     use super::nonterminals::NonTerminalSpan;
     use super::Cookie as FullyParsedCookie;
@@ -185,20 +1090,24 @@ mod terminals {
     pub struct Cookie {
         pub(super) key: NonTerminalSpan,
         pub(super) value: NonTerminalSpan,
+        pub(super) quoted: bool,
     }
 
     impl Cookie {
         pub(super) fn with_str<'a>(&self, data: &'a str) -> Result<FullyParsedCookie<'a>, Error> {
-            Ok(FullyParsedCookie {
-                name: self.key.as_str(data).map_err(InternalError::to_error)?,
-                value: self.value.as_str(data).map_err(InternalError::to_error)?,
-            })
+            Ok(FullyParsedCookie::with_quoted(
+                self.key.as_str(data).map_err(InternalError::into_error)?,
+                self.value.as_str(data).map_err(InternalError::into_error)?,
+                self.quoted,
+            )
+            .with_spans(self.key.range(), self.value.range()))
         }
     }
 }
 
-mod nonterminals {
+pub(crate) mod nonterminals {
     use super::{InternalError, InternalErrorKind};
+    use std::ops::Range;
 
     #[derive(Clone, Debug)]
     pub struct NonTerminalSpan {
@@ -208,10 +1117,11 @@ mod nonterminals {
 
     impl NonTerminalSpan {
         pub(crate) fn new(start: usize, end: usize) -> NonTerminalSpan {
-            NonTerminalSpan {
-                start: start,
-                end: end,
-            }
+            NonTerminalSpan { start, end }
+        }
+
+        pub(crate) fn range(&self) -> Range<usize> {
+            self.start..self.end
         }
 
         pub(crate) fn as_str<'a>(&self, data: &'a str) -> Result<&'a str, InternalError> {
@@ -226,8 +1136,10 @@ mod nonterminals {
 }
 
 #[cfg(test)]
+#[allow(clippy::redundant_static_lifetimes)]
 mod tests {
     use super::Cookie;
+    use crate::ParseErrorKind;
 
     #[test]
     fn get_name() {
@@ -237,6 +1149,9 @@ mod tests {
         let cookie = Cookie {
             name: COOKIE_KEY,
             value: COOKIE_VALUE,
+            quoted: false,
+            name_span: None,
+            value_span: None,
         };
 
         assert_eq!(COOKIE_KEY, cookie.get_name());
@@ -250,6 +1165,9 @@ mod tests {
         let cookie = Cookie {
             name: COOKIE_KEY,
             value: COOKIE_VALUE,
+            quoted: false,
+            name_span: None,
+            value_span: None,
         };
 
         assert_eq!(COOKIE_VALUE, cookie.get_value());
@@ -277,6 +1195,15 @@ mod tests {
         assert_eq!("quotedval", parsed_cookie.value);
     }
 
+    #[test]
+    fn single_cookie_quoted_flag() {
+        const COOKIE_STR: &str = "test=1234; quoted_test=\"quotedval\"";
+        let parsed_cookies = Cookie::parse(COOKIE_STR).unwrap();
+
+        assert!(!parsed_cookies[0].quoted);
+        assert!(parsed_cookies[1].quoted);
+    }
+
     #[test]
     fn single_cookie_with_equals_in_value() {
         const COOKIE_STR: &'static str = "test=abc=123";
@@ -518,4 +1445,164 @@ mod tests {
         assert_eq!("third_val", parsed_cookie_2.name);
         assert_eq!("v4lue", parsed_cookie_2.value);
     }
+
+    #[test]
+    fn display() {
+        const COOKIE_STR: &str = "a=1; b=\"2\"; c=";
+        let parsed_cookies = Cookie::parse(COOKIE_STR).unwrap();
+
+        assert_eq!("a=1", parsed_cookies[0].to_string());
+        assert_eq!("b=\"2\"", parsed_cookies[1].to_string());
+        assert_eq!("c=", parsed_cookies[2].to_string());
+    }
+
+    #[test]
+    fn eq_and_hash() {
+        use std::collections::HashSet;
+
+        const COOKIE_STR: &str = "a=1; a=1; a=\"1\"; b=1";
+        let parsed_cookies = Cookie::parse(COOKIE_STR).unwrap();
+
+        assert_eq!(parsed_cookies[0], parsed_cookies[1]);
+        assert_ne!(parsed_cookies[0], parsed_cookies[2]);
+        assert_eq!(
+            3,
+            parsed_cookies
+                .iter()
+                .cloned()
+                .collect::<HashSet<Cookie>>()
+                .len()
+        );
+    }
+
+    #[test]
+    fn disallowed_characters() {
+        for cookie_str in [
+            "a=b,c",
+            "a=b\\c",
+            "a=b; c=d\x01",
+            "a=\u{e9}",
+            "a=1; \u{e9}=2",
+            "a=b\r\n",
+        ] {
+            assert!(
+                matches!(Cookie::parse(cookie_str), Err(super::Error::ParseError(_))),
+                "input: {:?}",
+                cookie_str
+            );
+        }
+    }
+
+    #[test]
+    fn disallowed_character_error_message() {
+        const COOKIE_STR: &str = "a=b,c";
+        let err = Cookie::parse(COOKIE_STR).unwrap_err();
+
+        assert!(err.to_string().contains("',' at offset 3"), "{}", err);
+    }
+
+    fn parse_error(cookie_str: &str) -> super::ParseError {
+        match Cookie::parse(cookie_str) {
+            Err(super::Error::ParseError(err)) => err,
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_error_unexpected_character() {
+        const COOKIE_STR: &str = "a=b c";
+        let err = parse_error(COOKIE_STR);
+
+        assert_eq!(ParseErrorKind::UnexpectedCharacter('c'), err.kind());
+        assert_eq!(4, err.offset());
+    }
+
+    #[test]
+    fn parse_error_unexpected_end_of_input() {
+        let err = super::ParseError::new(b"a", 1, Vec::new());
+
+        assert_eq!(ParseErrorKind::UnexpectedEndOfInput, err.kind());
+        assert_eq!(1, err.offset());
+    }
+
+    #[test]
+    fn parse_error_unterminated_quote() {
+        const COOKIE_STR: &str = "a=\"b; c=d";
+        let err = parse_error(COOKIE_STR);
+
+        assert_eq!(ParseErrorKind::UnterminatedQuote, err.kind());
+        assert_eq!(4, err.offset());
+        assert!(err.expected().contains(&"DQUOTE"));
+    }
+
+    #[test]
+    fn parse_error_empty_cookie_pair() {
+        for (cookie_str, offset) in [("", 0), ("a=b;", 4), ("a=b; ;c=d", 5), ("a=b;;c=d", 4)] {
+            let err = parse_error(cookie_str);
+
+            assert_eq!(
+                ParseErrorKind::EmptyCookiePair,
+                err.kind(),
+                "{:?}",
+                cookie_str
+            );
+            assert_eq!(offset, err.offset(), "{:?}", cookie_str);
+        }
+    }
+
+    #[test]
+    fn parse_error_display() {
+        const COOKIE_STR: &str = "a=\"b";
+        let err = parse_error(COOKIE_STR);
+
+        assert!(err
+            .to_string()
+            .starts_with("Parse Error: unterminated quote at offset 4, expected "));
+    }
+
+    #[test]
+    fn parse_error_render() {
+        const COOKIE_STR: &str = "\ta=1; b=2 3";
+        let err = parse_error(COOKIE_STR);
+
+        assert_eq!(
+            "\ta=1; b=2 3\n\t         ^ unexpected character '3'",
+            err.render(COOKIE_STR)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::Cookie;
+
+        #[test]
+        fn serialize() {
+            let cookies = Cookie::parse("a=1; b=\"2\"").unwrap();
+
+            assert_eq!(
+                r#"[{"name":"a","value":"1","quoted":false},{"name":"b","value":"2","quoted":true}]"#,
+                serde_json::to_string(&cookies).unwrap()
+            );
+        }
+
+        #[test]
+        fn deserialize_borrowed() {
+            const JSON: &str = r#"{"name":"session","value":"abc"}"#;
+            let cookie: Cookie = serde_json::from_str(JSON).unwrap();
+
+            assert_eq!("session", cookie.get_name());
+            assert_eq!("abc", cookie.get_value());
+            assert!(!cookie.is_quoted());
+            assert_eq!(None, cookie.name_span());
+            assert!(JSON
+                .as_bytes()
+                .as_ptr_range()
+                .contains(&cookie.get_value().as_ptr()));
+        }
+
+        #[test]
+        fn deserialize_escaped() {
+            assert!(serde_json::from_str::<Cookie>(r#"{"name":"a\u0062","value":"1"}"#).is_err());
+        }
+    }
 }
@@ -0,0 +1,459 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Day names starting from Thursday, the day of the week of 1970-01-01.
+const DAY_NAMES: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+
+const MONTH_NAMES: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Parses a cookie date, such as the value of the `Expires` attribute of a `Set-Cookie` header,
+/// using the algorithm described in [RFC 6265, Section 5.1.1](https://tools.ietf.org/html/rfc6265.html#section-5.1.1),
+/// and returns the corresponding UTC timestamp.
+///
+/// Returns `None` if the input is not a valid cookie date.
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::parse_cookie_date;
+/// use std::time::{Duration, UNIX_EPOCH};
+///
+/// let expected = Some(UNIX_EPOCH + Duration::from_secs(784111777));
+///
+/// assert_eq!(expected, parse_cookie_date("Sun, 06 Nov 1994 08:49:37 GMT"));
+/// assert_eq!(expected, parse_cookie_date("Sunday, 06-Nov-94 08:49:37 GMT"));
+/// assert_eq!(expected, parse_cookie_date("Sun Nov  6 08:49:37 1994"));
+/// ```
+pub fn parse_cookie_date(input: &str) -> Option<SystemTime> {
+    let mut time: Option<(u32, u32, u32)> = None;
+    let mut day_of_month: Option<u32> = None;
+    let mut month: Option<u32> = None;
+    let mut year: Option<u32> = None;
+
+    for date_token in input
+        .as_bytes()
+        .split(|b| matching::is_delimiter(*b))
+        .filter(|date_token| !date_token.is_empty())
+    {
+        if time.is_none() {
+            if let Some(hms) = matching::time(date_token) {
+                time = Some(hms);
+                continue;
+            }
+        }
+
+        if day_of_month.is_none() {
+            if let Some(value) = matching::digits_prefix(date_token, 1, 2) {
+                day_of_month = Some(value);
+                continue;
+            }
+        }
+
+        if month.is_none() {
+            if let Some(value) = matching::month(date_token) {
+                month = Some(value);
+                continue;
+            }
+        }
+
+        if year.is_none() {
+            if let Some(value) = matching::digits_prefix(date_token, 2, 4) {
+                year = Some(value);
+                continue;
+            }
+        }
+    }
+
+    let (hour, minute, second) = time?;
+    let day_of_month = day_of_month?;
+    let month = month?;
+    let year = match year? {
+        year @ 70..=99 => year + 1900,
+        year @ 0..=69 => year + 2000,
+        year => year,
+    };
+
+    if day_of_month < 1
+        || day_of_month > days_in_month(i64::from(year), month)
+        || year < 1601
+        || hour > 23
+        || minute > 59
+        || second > 59
+    {
+        return None;
+    }
+
+    let days = days_from_civil(i64::from(year), month, day_of_month);
+    Some(system_time_from_unix_seconds(
+        days * SECONDS_PER_DAY + i64::from(hour * 3600 + minute * 60 + second),
+    ))
+}
+
+/// Formats a timestamp as an [IMF-fixdate](https://tools.ietf.org/html/rfc7231#section-7.1.1.1),
+/// such as `Sun, 06 Nov 1994 08:49:37 GMT`. Fractions of a second are truncated.
+///
+/// Returns `None` if the year is not between 1601 and 9999, as such dates
+/// cannot be parsed back as a cookie date.
+pub(crate) fn format_imf_fixdate(time: SystemTime) -> Option<String> {
+    let seconds = unix_seconds_from_system_time(time);
+    let days = seconds.div_euclid(SECONDS_PER_DAY);
+    let seconds_of_day = seconds.rem_euclid(SECONDS_PER_DAY);
+    let (year, month, day) = civil_from_days(days);
+
+    if !(1601..=9999).contains(&year) {
+        return None;
+    }
+
+    Some(format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+        DAY_NAMES[days.rem_euclid(7) as usize],
+        day,
+        MONTH_NAMES[month as usize - 1],
+        year,
+        seconds_of_day / 3600,
+        seconds_of_day / 60 % 60,
+        seconds_of_day % 60
+    ))
+}
+
+/// The latest time which `SystemTime` can represent on this platform, in whole seconds.
+pub(crate) fn latest_system_time() -> SystemTime {
+    let mut latest = UNIX_EPOCH;
+    let mut step = Duration::from_secs(u64::MAX);
+
+    while step >= Duration::from_secs(1) {
+        match latest.checked_add(step) {
+            Some(time) => latest = time,
+            None => step /= 2,
+        }
+    }
+
+    latest
+}
+
+/// Seconds since the Unix epoch, rounded towards negative infinity.
+pub(crate) fn unix_seconds_from_system_time(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => i64::try_from(duration.as_secs()).unwrap_or(i64::MAX),
+        Err(err) => {
+            let duration = err.duration();
+            let seconds = i64::try_from(duration.as_secs()).unwrap_or(i64::MAX);
+            if duration.subsec_nanos() > 0 {
+                -seconds - 1
+            } else {
+                -seconds
+            }
+        }
+    }
+}
+
+pub(crate) fn system_time_from_unix_seconds(seconds: i64) -> SystemTime {
+    if seconds >= 0 {
+        UNIX_EPOCH + Duration::from_secs(seconds as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_secs(seconds.unsigned_abs())
+    }
+}
+
+fn is_leap_year(year: i64) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+mod matching {
+    use super::MONTH_NAMES;
+
+    /// `delimiter = %x09 / %x20-2F / %x3B-40 / %x5B-60 / %x7B-7E`
+    pub fn is_delimiter(b: u8) -> bool {
+        matches!(b, 0x09 | 0x20..=0x2f | 0x3b..=0x40 | 0x5b..=0x60 | 0x7b..=0x7e)
+    }
+
+    /// Matches between `min` and `max` digits, followed by either the end
+    /// of the token or a non-digit and any octets.
+    pub fn digits_prefix(token: &[u8], min: usize, max: usize) -> Option<u32> {
+        let (value, rest) = digits(token, min, max)?;
+        match rest.first() {
+            Some(b) if b.is_ascii_digit() => None,
+            _ => Some(value),
+        }
+    }
+
+    /// `time = hms-time ( non-digit *OCTET )`
+    pub fn time(token: &[u8]) -> Option<(u32, u32, u32)> {
+        let (hour, rest) = digits(token, 1, 2)?;
+        let rest = rest.strip_prefix(b":")?;
+        let (minute, rest) = digits(rest, 1, 2)?;
+        let rest = rest.strip_prefix(b":")?;
+        let second = digits_prefix(rest, 1, 2)?;
+        Some((hour, minute, second))
+    }
+
+    /// `month = ( "jan" / "feb" / ... / "dec" ) *OCTET`, case-insensitively.
+    pub fn month(token: &[u8]) -> Option<u32> {
+        let prefix = token.get(0..3)?;
+        MONTH_NAMES
+            .iter()
+            .position(|name| name.as_bytes().eq_ignore_ascii_case(prefix))
+            .map(|idx| idx as u32 + 1)
+    }
+
+    fn digits(token: &[u8], min: usize, max: usize) -> Option<(u32, &[u8])> {
+        let count = token
+            .iter()
+            .take(max)
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+
+        if count < min {
+            return None;
+        }
+
+        let value = token[..count]
+            .iter()
+            .fold(0_u32, |acc, b| acc * 10 + u32::from(b - b'0'));
+        Some((value, &token[count..]))
+    }
+}
+
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Number of days since 1970-01-01 in the proleptic Gregorian calendar,
+/// using Howard Hinnant's `days_from_civil` algorithm.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = i64::from(month);
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of `days_from_civil`, returns `(year, month, day)`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        civil_from_days, days_from_civil, format_imf_fixdate, parse_cookie_date,
+        system_time_from_unix_seconds,
+    };
+    use std::time::Duration;
+
+    const NOV_6_1994: i64 = 784_111_777;
+
+    #[test]
+    fn epoch() {
+        assert_eq!(0, days_from_civil(1970, 1, 1));
+    }
+
+    #[test]
+    fn before_epoch() {
+        assert_eq!(-1, days_from_civil(1969, 12, 31));
+    }
+
+    #[test]
+    fn leap_day() {
+        assert_eq!(11_016, days_from_civil(2000, 2, 29));
+    }
+
+    #[test]
+    fn imf_fixdate() {
+        assert_eq!(
+            Some(system_time_from_unix_seconds(1_445_412_480)),
+            parse_cookie_date("Wed, 21 Oct 2015 07:28:00 GMT")
+        );
+    }
+
+    #[test]
+    fn rfc_850() {
+        assert_eq!(
+            Some(system_time_from_unix_seconds(NOV_6_1994)),
+            parse_cookie_date("Sunday, 06-Nov-94 08:49:37 GMT")
+        );
+    }
+
+    #[test]
+    fn asctime() {
+        assert_eq!(
+            Some(system_time_from_unix_seconds(NOV_6_1994)),
+            parse_cookie_date("Sun Nov  6 08:49:37 1994")
+        );
+    }
+
+    #[test]
+    fn netscape_draft() {
+        assert_eq!(
+            Some(system_time_from_unix_seconds(NOV_6_1994)),
+            parse_cookie_date("Sun, 06-Nov-1994 08:49:37 GMT")
+        );
+    }
+
+    #[test]
+    fn single_digit_time_fields() {
+        assert_eq!(
+            Some(system_time_from_unix_seconds(
+                NOV_6_1994 - 8 * 3600 - 49 * 60 - 37
+            )),
+            parse_cookie_date("6 Nov 1994 0:0:0")
+        );
+    }
+
+    #[test]
+    fn case_insensitive_month_with_suffix() {
+        assert_eq!(
+            Some(system_time_from_unix_seconds(NOV_6_1994)),
+            parse_cookie_date("NOVEMBER 6 1994 08:49:37")
+        );
+    }
+
+    #[test]
+    fn any_order_and_delimiters() {
+        assert_eq!(
+            Some(system_time_from_unix_seconds(NOV_6_1994)),
+            parse_cookie_date("1994/Nov/06 08:49:37GMT+0100")
+        );
+    }
+
+    #[test]
+    fn two_digit_year_fixups() {
+        assert_eq!(
+            Some(system_time_from_unix_seconds(0)),
+            parse_cookie_date("01 Jan 70 00:00:00 GMT")
+        );
+        assert_eq!(
+            Some(system_time_from_unix_seconds(3_124_224_000)),
+            parse_cookie_date("01 Jan 69 00:00:00 GMT")
+        );
+    }
+
+    #[test]
+    fn before_epoch_date() {
+        assert_eq!(
+            Some(system_time_from_unix_seconds(-11_644_473_600)),
+            parse_cookie_date("Mon, 01 Jan 1601 00:00:00 GMT")
+        );
+    }
+
+    #[test]
+    fn year_before_1601() {
+        assert_eq!(None, parse_cookie_date("Sun, 31 Dec 1600 23:59:59 GMT"));
+    }
+
+    #[test]
+    fn invalid_day_of_month() {
+        assert_eq!(None, parse_cookie_date("Thu, 31 Nov 1994 08:49:37 GMT"));
+        assert_eq!(None, parse_cookie_date("Sun, 00 Nov 1994 08:49:37 GMT"));
+    }
+
+    #[test]
+    fn february_29() {
+        assert!(parse_cookie_date("Tue, 29 Feb 2000 00:00:00 GMT").is_some());
+        assert_eq!(None, parse_cookie_date("Thu, 29 Feb 2001 00:00:00 GMT"));
+    }
+
+    #[test]
+    fn invalid_time() {
+        assert_eq!(None, parse_cookie_date("Sun, 06 Nov 1994 24:49:37 GMT"));
+        assert_eq!(None, parse_cookie_date("Sun, 06 Nov 1994 08:60:37 GMT"));
+        assert_eq!(None, parse_cookie_date("Sun, 06 Nov 1994 08:49:60 GMT"));
+    }
+
+    #[test]
+    fn time_with_three_digit_field() {
+        assert_eq!(None, parse_cookie_date("Sun, 06 Nov 1994 008:49:37 GMT"));
+    }
+
+    #[test]
+    fn missing_component() {
+        assert_eq!(None, parse_cookie_date("Sun, 06 Nov 08:49:37 GMT"));
+        assert_eq!(None, parse_cookie_date("Sun, 06 1994 08:49:37 GMT"));
+        assert_eq!(None, parse_cookie_date("Sun, 06 Nov 1994 GMT"));
+        assert_eq!(None, parse_cookie_date(""));
+    }
+
+    #[test]
+    fn non_ascii() {
+        assert_eq!(
+            Some(system_time_from_unix_seconds(NOV_6_1994)),
+            parse_cookie_date("Sün, 06 Nov 1994 08:49:37 GMT")
+        );
+    }
+
+    #[test]
+    fn civil_from_days_round_trip() {
+        for days in [-135_140, -1, 0, 11_016, 16_729, 2_932_896] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days, days_from_civil(year, month, day));
+        }
+    }
+
+    #[test]
+    fn format() {
+        assert_eq!(
+            Some("Sun, 06 Nov 1994 08:49:37 GMT".to_string()),
+            format_imf_fixdate(system_time_from_unix_seconds(NOV_6_1994))
+        );
+    }
+
+    #[test]
+    fn format_truncates_fractions() {
+        assert_eq!(
+            Some("Sun, 06 Nov 1994 08:49:37 GMT".to_string()),
+            format_imf_fixdate(
+                system_time_from_unix_seconds(NOV_6_1994) + Duration::from_millis(999)
+            )
+        );
+    }
+
+    #[test]
+    fn format_before_epoch() {
+        assert_eq!(
+            Some("Mon, 01 Jan 1601 00:00:00 GMT".to_string()),
+            format_imf_fixdate(system_time_from_unix_seconds(-11_644_473_600))
+        );
+    }
+
+    #[test]
+    fn format_out_of_range() {
+        assert_eq!(
+            None,
+            format_imf_fixdate(system_time_from_unix_seconds(-11_644_473_601))
+        );
+    }
+
+    #[test]
+    fn format_round_trip() {
+        let time = system_time_from_unix_seconds(1_445_412_480);
+        assert_eq!(
+            Some(time),
+            format_imf_fixdate(time).and_then(|formatted| parse_cookie_date(&formatted))
+        );
+    }
+}
@@ -0,0 +1,527 @@
+use super::cookie::ParseError;
+use super::parser::ParseDiagnostic;
+use super::{ByteCookie, Cookie, CookieLexer, Error};
+use std::ops::Range;
+
+/// A lazy iterator over the cookies of a `Cookie` header, created by [`Cookie::iter`].
+///
+/// The input is parsed a single cookie at a time, without allocating. Once an error has been
+/// returned, the iterator yields no further items.
+#[derive(Clone, Debug)]
+pub struct CookieIter<'a> {
+    input: &'a str,
+    spans: CookieSpans<'a>,
+}
+
+impl<'a> CookieIter<'a> {
+    pub(crate) fn new(input: &'a str) -> CookieIter<'a> {
+        CookieIter {
+            input,
+            spans: CookieSpans::new(input.as_bytes()),
+        }
+    }
+}
+
+impl<'a> Iterator for CookieIter<'a> {
+    type Item = Result<Cookie<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.spans
+            .next()
+            .map(|span| span.map(|span| span.into_cookie(self.input)))
+    }
+}
+
+impl std::iter::FusedIterator for CookieIter<'_> {}
+
+/// Same as [`CookieIter`], but for input which is not known to be UTF-8.
+#[derive(Clone, Debug)]
+pub(crate) struct ByteCookieIter<'a> {
+    input: &'a [u8],
+    spans: CookieSpans<'a>,
+}
+
+impl<'a> ByteCookieIter<'a> {
+    pub(crate) fn new(input: &'a [u8]) -> ByteCookieIter<'a> {
+        ByteCookieIter {
+            input,
+            spans: CookieSpans::new(input),
+        }
+    }
+}
+
+impl<'a> Iterator for ByteCookieIter<'a> {
+    type Item = Result<ByteCookie<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.spans.next().map(|span| {
+            span.map(|span| {
+                ByteCookie::new(
+                    &self.input[span.name.clone()],
+                    &self.input[span.value.clone()],
+                    span.quoted,
+                    span.name,
+                    span.value,
+                )
+            })
+        })
+    }
+}
+
+const EXPECTED_COOKIE_PAIR: &[&str] = &["DQUOTE", "\"=\"", "cookie-octet"];
+const EXPECTED_COOKIE_END: &[&str] = &["SP", "\";\"", "HTAB"];
+const EXPECTED_WHITESPACE: &[&str] = &["SP", "HTAB"];
+
+fn expected_value_chars(after_name: bool) -> &'static [&'static str] {
+    if after_name {
+        &["\"=\"", "cookie-octet"]
+    } else {
+        &["cookie-octet"]
+    }
+}
+
+fn expected_value_chars_or_quote(after_name: bool) -> &'static [&'static str] {
+    if after_name {
+        &["DQUOTE", "\"=\"", "cookie-octet"]
+    } else {
+        &["DQUOTE", "cookie-octet"]
+    }
+}
+
+/// Same as [`CookieIter`], but skips the cookies which cannot be parsed,
+/// returning a diagnostic for each of them instead of stopping.
+#[derive(Clone, Debug)]
+pub(crate) struct RecoveringCookieIter<'a> {
+    input: &'a str,
+    spans: CookieSpans<'a>,
+}
+
+impl<'a> RecoveringCookieIter<'a> {
+    pub(crate) fn new(input: &'a str) -> RecoveringCookieIter<'a> {
+        RecoveringCookieIter {
+            input,
+            spans: CookieSpans::new(input.as_bytes()),
+        }
+    }
+}
+
+impl<'a> Iterator for RecoveringCookieIter<'a> {
+    type Item = Result<Cookie<'a>, ParseDiagnostic>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.spans.next_recovering().map(|span| {
+            span.map(|span| span.into_cookie(self.input))
+                .map_err(|(span, err)| ParseDiagnostic::new(span, err))
+        })
+    }
+}
+
+/// The location of a cookie in the input.
+struct CookieSpan {
+    name: Range<usize>,
+    value: Range<usize>,
+    quoted: bool,
+}
+
+impl CookieSpan {
+    fn into_cookie(self, input: &str) -> Cookie<'_> {
+        Cookie::with_quoted(
+            &input[self.name.clone()],
+            &input[self.value.clone()],
+            self.quoted,
+        )
+        .with_spans(self.name, self.value)
+    }
+}
+
+/// Parses the input byte by byte. As every character of the grammar is ASCII,
+/// the spans always fall on character boundaries when the input is a `str`.
+#[derive(Clone, Debug)]
+struct CookieSpans<'a> {
+    input: &'a [u8],
+    cursor: usize,
+    state: State,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Start,
+    Separator,
+    Done,
+}
+
+impl<'a> CookieSpans<'a> {
+    fn new(input: &'a [u8]) -> CookieSpans<'a> {
+        CookieSpans {
+            input,
+            cursor: 0,
+            state: State::Start,
+        }
+    }
+
+    fn byte_at(&self, idx: usize) -> Option<u8> {
+        self.input.get(idx).copied()
+    }
+
+    /// Finds the end of the run of bytes starting at `start` which satisfy `predicate`.
+    fn scan_while<F: Fn(char) -> bool>(&self, start: usize, predicate: F) -> usize {
+        self.input[start..]
+            .iter()
+            .position(|b| !predicate(char::from(*b)))
+            .map_or(self.input.len(), |len| start + len)
+    }
+
+    fn parse_cookie(&mut self) -> Result<CookieSpan, ParseError> {
+        let start = self.cursor;
+        let name_end = self.scan_while(start, CookieLexer::is_token_char);
+
+        if self.byte_at(name_end) == Some(b'=') {
+            self.cursor = name_end + 1;
+            let (value, quoted) = self.parse_value(true)?;
+            Ok(CookieSpan {
+                name: start..name_end,
+                value,
+                quoted,
+            })
+        } else {
+            let (value, quoted) = self.parse_value(false)?;
+            Ok(CookieSpan {
+                name: start..start,
+                value,
+                quoted,
+            })
+        }
+    }
+
+    /// Parses a possibly quoted value. Values which follow a name may contain `=`
+    /// and may be empty, while values without a name may not.
+    fn parse_value(&mut self, after_name: bool) -> Result<(Range<usize>, bool), ParseError> {
+        let is_value_char =
+            |c: char| (after_name && c == '=') || CookieLexer::is_cookie_octet_char(c);
+
+        if self.byte_at(self.cursor) == Some(b'"') {
+            let value_start = self.cursor + 1;
+            let value_end = self.scan_while(value_start, is_value_char);
+            if value_end == value_start {
+                return Err(self.error_at(value_end, expected_value_chars(after_name)));
+            } else if self.byte_at(value_end) != Some(b'"') {
+                return Err(self.error_at(value_end, expected_value_chars_or_quote(after_name)));
+            }
+
+            self.cursor = value_end + 1;
+            Ok((value_start..value_end, true))
+        } else {
+            let value_start = self.cursor;
+            let value_end = self.scan_while(value_start, is_value_char);
+            if !after_name && value_end == value_start {
+                return Err(self.error_at(value_end, EXPECTED_COOKIE_PAIR));
+            }
+
+            self.cursor = value_end;
+            Ok((value_start..value_end, false))
+        }
+    }
+
+    /// Checks what follows a cookie, which is either a separator, or optional whitespace
+    /// followed by the end of the input.
+    fn parse_cookie_end(&mut self) -> Result<State, ParseError> {
+        match self.byte_at(self.cursor) {
+            None => Ok(State::Done),
+            Some(b';') => Ok(State::Separator),
+            Some(b) if CookieLexer::is_whitespace_char(char::from(b)) => {
+                self.skip_whitespace();
+                match self.byte_at(self.cursor) {
+                    None => Ok(State::Done),
+                    Some(_) => Err(self.error_at(self.cursor, EXPECTED_WHITESPACE)),
+                }
+            }
+            Some(_) => Err(self.error_at(self.cursor, EXPECTED_COOKIE_END)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.cursor = self.scan_while(self.cursor, CookieLexer::is_whitespace_char);
+    }
+
+    /// Skips a `;`, and the single space which may follow it. If there is more than one space,
+    /// none of them are skipped, so that the error points at the first one.
+    fn skip_separator(&mut self) {
+        self.cursor += 1;
+        if self.byte_at(self.cursor) == Some(b' ') && self.byte_at(self.cursor + 1) != Some(b' ') {
+            self.cursor += 1;
+        }
+    }
+
+    /// Creates an error for the character at `location`. Characters which are not allowed
+    /// anywhere in the input are reported without `expected`, the same way as the grammar does.
+    fn error_at(&self, location: usize, expected: &[&'static str]) -> ParseError {
+        let is_allowed = self.byte_at(location).is_none_or(|b| {
+            let c = char::from(b);
+            matches!(c, '=' | ';' | '"')
+                || CookieLexer::is_whitespace_char(c)
+                || CookieLexer::is_cookie_octet_char(c)
+        });
+
+        let expected = if is_allowed {
+            expected.to_vec()
+        } else {
+            Vec::new()
+        };
+
+        ParseError::new(self.input, location, expected)
+    }
+
+    /// Parses the next cookie, returning where it starts along with the result.
+    fn parse_next(&mut self) -> Option<(usize, Result<CookieSpan, ParseError>)> {
+        match self.state {
+            State::Start => self.skip_whitespace(),
+            State::Separator => self.skip_separator(),
+            State::Done => return None,
+        }
+
+        let start = self.cursor;
+        let result = self.parse_cookie().and_then(|span| {
+            self.state = self.parse_cookie_end()?;
+            Ok(span)
+        });
+
+        if result.is_err() {
+            self.state = State::Done;
+        }
+
+        Some((start, result))
+    }
+
+    /// Same as `next`, but instead of stopping at an error, skips to the next `;`,
+    /// and returns the span of the skipped input along with the error.
+    fn next_recovering(&mut self) -> Option<Result<CookieSpan, (Range<usize>, ParseError)>> {
+        let (start, result) = self.parse_next()?;
+
+        Some(result.map_err(|err| {
+            let end = self.input[err.offset()..]
+                .iter()
+                .position(|b| *b == b';')
+                .map_or(self.input.len(), |len| err.offset() + len);
+
+            self.cursor = end;
+            if end < self.input.len() {
+                self.state = State::Separator;
+            }
+
+            (start..end, err)
+        }))
+    }
+}
+
+impl Iterator for CookieSpans<'_> {
+    type Item = Result<CookieSpan, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.parse_next()
+            .map(|(_, result)| result.map_err(ParseError::into_error))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ByteCookieIter, CookieIter};
+    use crate::{Cookie, Error};
+
+    const INPUTS: &[&str] = &[
+        "test=1234",
+        "test1=01234; test2=testval",
+        "test1=0x1234; test2=test2; third_val=v4lue",
+        "test1=0x1234;test2=test2;third_val=v4lue",
+        " test1=0x1234; test2=test2; third_val=v4lue",
+        "test1=0x1234; test2=test2; third_val=v4lue   ",
+        "   test1=0x1234; test2=test2; third_val=v4lue ",
+        " \x09 ztest=9876",
+        " \x09 ztest=9876       ",
+        "abcde=77766test \x09\x09    ",
+        " qtest=9878",
+        "xyzzz=test3 ",
+        "quoted_test=\"quotedval\"",
+        "test=abc=123",
+        "nokey",
+        "\"nokey\"",
+        "=nokey",
+        " =nokey",
+        "=",
+        "==",
+        "noval=",
+        "noval= ",
+        "a=\"b=c\"",
+        "a=1; a=1; a=\"1\"; b=1",
+        "a=1; b=\"2\"; c=",
+        "session=abc; _ga=GA1.2; theme=\"dark\"",
+        "a=(b)",
+        "",
+        "   ",
+        "a=b;",
+        "a=b; ",
+        "a=b;  c=d",
+        "a=b;\tc=d",
+        "a=b; \tc=d",
+        "a=b;   ",
+        "a=b ;c=d",
+        "a=b c",
+        "a=\"\"",
+        "a=\"b",
+        "a=\"b\"c",
+        "a(b=c",
+        "nokey=\"a\"; b",
+        "\"a=b\"",
+        "a=b,c",
+        "a=b\\c",
+        "a=\u{e9}",
+        "a=b\r\n",
+    ];
+
+    fn parsed(input: &str) -> Result<Vec<(String, String)>, ()> {
+        Cookie::parse_with_grammar(input)
+            .map(|cookies| {
+                cookies
+                    .iter()
+                    .map(|c| (c.get_name().to_string(), c.get_value().to_string()))
+                    .collect()
+            })
+            .map_err(|_| ())
+    }
+
+    fn iterated(input: &str) -> Result<Vec<(String, String)>, ()> {
+        CookieIter::new(input)
+            .map(|cookie| cookie.map(|c| (c.get_name().to_string(), c.get_value().to_string())))
+            .collect::<Result<Vec<_>, Error>>()
+            .map_err(|_| ())
+    }
+
+    #[test]
+    fn same_as_grammar() {
+        for input in INPUTS {
+            assert_eq!(parsed(input), iterated(input), "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn same_errors_as_grammar() {
+        for input in INPUTS {
+            if let Err(Error::ParseError(expected_err)) = Cookie::parse_with_grammar(input) {
+                match CookieIter::new(input).find_map(Result::err) {
+                    Some(Error::ParseError(err)) => {
+                        assert_eq!(expected_err.kind(), err.kind(), "input: {:?}", input);
+                        assert_eq!(expected_err.offset(), err.offset(), "input: {:?}", input);
+                    }
+                    other => panic!("input: {:?}, result: {:?}", input, other),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn same_quoted_flags_as_grammar() {
+        for input in INPUTS {
+            if let Ok(cookies) = Cookie::parse_with_grammar(input) {
+                assert_eq!(
+                    cookies.iter().map(|c| c.is_quoted()).collect::<Vec<_>>(),
+                    CookieIter::new(input)
+                        .map(|c| c.unwrap().is_quoted())
+                        .collect::<Vec<_>>(),
+                    "input: {:?}",
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn same_spans_as_grammar() {
+        for input in INPUTS {
+            if let Ok(cookies) = Cookie::parse_with_grammar(input) {
+                assert_eq!(
+                    cookies
+                        .iter()
+                        .map(|c| (c.name_span(), c.value_span()))
+                        .collect::<Vec<_>>(),
+                    CookieIter::new(input)
+                        .map(|c| c.unwrap())
+                        .map(|c| (c.name_span(), c.value_span()))
+                        .collect::<Vec<_>>(),
+                    "input: {:?}",
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn lazy() {
+        const COOKIE_STR: &str = "a=1; b=2; c";
+        let mut iter = CookieIter::new(COOKIE_STR);
+
+        let cookie = iter.next().unwrap().unwrap();
+        assert_eq!("a", cookie.get_name());
+        assert_eq!("1", cookie.get_value());
+
+        let cookie = iter.next().unwrap().unwrap();
+        assert_eq!("b", cookie.get_name());
+        assert_eq!("2", cookie.get_value());
+
+        let cookie = iter.next().unwrap().unwrap();
+        assert_eq!("", cookie.get_name());
+        assert_eq!("c", cookie.get_value());
+
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn stops_after_error() {
+        const COOKIE_STR: &str = "a=1; b=2 3; c=4";
+        let mut iter = CookieIter::new(COOKIE_STR);
+
+        assert!(iter.next().unwrap().is_ok());
+        assert!(matches!(iter.next(), Some(Err(Error::ParseError(_)))));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn invalid_character() {
+        const COOKIE_STR: &str = "a=1; b=\u{e9}";
+        let mut iter = CookieIter::new(COOKIE_STR);
+
+        assert!(iter.next().unwrap().is_ok());
+        assert!(matches!(iter.next(), Some(Err(Error::ParseError(_)))));
+    }
+
+    #[test]
+    fn same_as_str() {
+        for input in INPUTS {
+            let cookies = CookieIter::new(input)
+                .map(|cookie| cookie.map_err(|_| ()))
+                .collect::<Result<Vec<_>, ()>>();
+            let byte_cookies = ByteCookieIter::new(input.as_bytes())
+                .map(|cookie| cookie.map_err(|_| ()))
+                .collect::<Result<Vec<_>, ()>>();
+
+            match (cookies, byte_cookies) {
+                (Ok(cookies), Ok(byte_cookies)) => {
+                    assert_eq!(cookies.len(), byte_cookies.len(), "input: {:?}", input);
+                    for (cookie, byte_cookie) in cookies.iter().zip(byte_cookies.iter()) {
+                        assert_eq!(*cookie, byte_cookie.to_cookie().unwrap());
+                    }
+                }
+                (Err(()), Err(())) => {}
+                _ => panic!("input: {:?}", input),
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_utf8() {
+        const COOKIE_BYTES: &[u8] = b"a=1; b=\xff\xfe";
+        let mut iter = ByteCookieIter::new(COOKIE_BYTES);
+
+        assert_eq!(b"a", iter.next().unwrap().unwrap().get_name());
+        assert!(matches!(iter.next(), Some(Err(Error::ParseError(_)))));
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,1178 @@
+use super::cookie::{
+    NetscapeError, NetscapeErrorKind, PrefixError, PrefixErrorKind, StorageError, StorageErrorKind,
+};
+use super::netscape::{self, NetscapeLine};
+use super::request_url::RequestUrl;
+#[cfg(feature = "public-suffix")]
+use super::PublicSuffixList;
+use super::{cookie_date, cookie_matching, cookie_serializer, Cookie, Error, SameSite, SetCookie};
+#[cfg(feature = "public-suffix")]
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// An in-memory cookie store, implementing the storage model described in
+/// [RFC 6265, Section 5.3](https://tools.ietf.org/html/rfc6265.html#section-5.3).
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::CookieJar;
+///
+/// let mut jar = CookieJar::new();
+/// jar.store("id=a3fWa; Path=/docs", "https://example.com/docs/index.html").unwrap();
+///
+/// assert_eq!(
+///     Some("id=a3fWa".to_string()),
+///     jar.cookie_header("https://example.com/docs/web").unwrap()
+/// );
+/// assert_eq!(None, jar.cookie_header("https://example.com/").unwrap());
+/// ```
+///
+/// With the `serde` feature, only the cookies are serialized. A jar which used a
+/// `PublicSuffixList` has no list after it is deserialized,
+/// so the list has to be set again with `set_public_suffix_list`, otherwise cookies
+/// for public suffixes are accepted.
+#[derive(Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CookieJar {
+    cookies: Vec<StoredCookie>,
+    #[cfg(feature = "public-suffix")]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    public_suffix_list: Option<Arc<PublicSuffixList>>,
+}
+
+impl CookieJar {
+    /// Creates an empty cookie jar.
+    pub fn new() -> CookieJar {
+        CookieJar::default()
+    }
+
+    /// Creates an empty cookie jar which ignores cookies whose `Domain` attribute
+    /// is a public suffix, as described in step 5 of
+    /// [RFC 6265, Section 5.3](https://tools.ietf.org/html/rfc6265.html#section-5.3).
+    ///
+    /// If the `Domain` attribute is identical to the host of the request URL,
+    /// the cookie is stored as a host-only cookie instead.
+    ///
+    /// The list may be given as an `Arc`, so that it can be shared between jars.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{CookieJar, Error, PublicSuffixList, StorageErrorKind};
+    ///
+    /// let mut jar = CookieJar::with_public_suffix_list(PublicSuffixList::embedded());
+    ///
+    /// match jar.store("id=a3fWa; Domain=co.uk", "https://example.co.uk/") {
+    ///     Err(Error::StorageError(err)) => assert_eq!(StorageErrorKind::PublicSuffix, err.kind()),
+    ///     _ => panic!("the cookie should have been rejected"),
+    /// }
+    /// ```
+    #[cfg(feature = "public-suffix")]
+    pub fn with_public_suffix_list<L: Into<Arc<PublicSuffixList>>>(
+        public_suffix_list: L,
+    ) -> CookieJar {
+        CookieJar {
+            cookies: Vec::new(),
+            public_suffix_list: Some(public_suffix_list.into()),
+        }
+    }
+
+    /// Makes the jar ignore cookies whose `Domain` attribute is a public suffix from now on,
+    /// like [`with_public_suffix_list`](CookieJar::with_public_suffix_list) does.
+    /// Cookies which are already in the jar are kept.
+    #[cfg(feature = "public-suffix")]
+    pub fn set_public_suffix_list<L: Into<Arc<PublicSuffixList>>>(
+        &mut self,
+        public_suffix_list: L,
+    ) {
+        self.public_suffix_list = Some(public_suffix_list.into());
+    }
+
+    /// Stores the cookie from the value of a `Set-Cookie` header, received in the response
+    /// to a request to `request_url`.
+    ///
+    /// Returns an error if the header cannot be parsed, or the cookie is rejected
+    /// by the storage model.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{CookieJar, Error, StorageErrorKind};
+    ///
+    /// let mut jar = CookieJar::new();
+    ///
+    /// match jar.store("id=a3fWa; Domain=example.org", "https://example.com/") {
+    ///     Err(Error::StorageError(err)) => assert_eq!(StorageErrorKind::DomainMismatch, err.kind()),
+    ///     _ => panic!("the cookie should have been rejected"),
+    /// }
+    /// ```
+    pub fn store(&mut self, set_cookie: &str, request_url: &str) -> Result<(), Error> {
+        self.store_at(set_cookie, request_url, SystemTime::now())
+    }
+
+    /// Same as [`store`](CookieJar::store), but uses `now` as the current time.
+    pub fn store_at(
+        &mut self,
+        set_cookie: &str,
+        request_url: &str,
+        now: SystemTime,
+    ) -> Result<(), Error> {
+        let request_url = parse_request_url(request_url)?;
+        let set_cookie = SetCookie::parse(set_cookie)?;
+        if set_cookie.has_prefix() && !request_url.is_secure() {
+            return Err(PrefixError::new(PrefixErrorKind::SecureRequestUrlRequired).into_error());
+        }
+
+        let domain_is_public_suffix = set_cookie
+            .get_domain()
+            .is_some_and(|domain| self.is_public_suffix(domain));
+        let cookie =
+            StoredCookie::from_set_cookie(&set_cookie, &request_url, now, domain_is_public_suffix)?;
+
+        self.insert(cookie);
+        self.remove_expired(now);
+
+        Ok(())
+    }
+
+    /// Loads the cookies from a Netscape `cookies.txt` file, the format used by curl, wget
+    /// and many browser extensions.
+    ///
+    /// Each line holds the domain, whether subdomains are included, the path, whether the cookie
+    /// is secure-only, the expiry as seconds since the Unix epoch or 0 for session cookies,
+    /// the name and the value, separated by tabs. Lines starting with `#HttpOnly_` hold `HttpOnly` cookies,
+    /// other lines starting with `#` are comments.
+    ///
+    /// If a line cannot be read, fails with [`Error::NetscapeError`] without loading any cookies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{CookieJar, Error, NetscapeErrorKind};
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.load_netscape(
+    ///     "# Netscape HTTP Cookie File\n\
+    ///      .example.com\tTRUE\t/\tTRUE\t0\tid\ta3fWa\n\
+    ///      #HttpOnly_example.com\tFALSE\t/docs\tFALSE\t0\tsession\tabc\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(
+    ///     Some("session=abc; id=a3fWa".to_string()),
+    ///     jar.cookie_header("https://example.com/docs/web").unwrap()
+    /// );
+    ///
+    /// match jar.load_netscape("example.com\tTRUE\t/\n") {
+    ///     Err(Error::NetscapeError(err)) => {
+    ///         assert_eq!(1, err.line());
+    ///         assert_eq!(NetscapeErrorKind::FieldCount, err.kind());
+    ///     }
+    ///     _ => panic!("the line should have been rejected"),
+    /// }
+    /// ```
+    pub fn load_netscape(&mut self, input: &str) -> Result<(), Error> {
+        self.load_netscape_at(input, SystemTime::now())
+    }
+
+    /// Same as [`load_netscape`](CookieJar::load_netscape), but uses `now` as the current time.
+    pub fn load_netscape_at(&mut self, input: &str, now: SystemTime) -> Result<(), Error> {
+        let cookies = netscape::parse(input)?
+            .iter()
+            .map(|line| StoredCookie::from_netscape_line(line, now))
+            .collect::<Result<Vec<StoredCookie>, Error>>()?;
+
+        for cookie in cookies {
+            self.insert(cookie);
+        }
+        self.remove_expired(now);
+
+        Ok(())
+    }
+
+    /// Writes every stored cookie in the format read by [`load_netscape`](CookieJar::load_netscape).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::CookieJar;
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.store("id=a3fWa; Secure; HttpOnly", "https://example.com/").unwrap();
+    ///
+    /// assert_eq!(
+    ///     "# Netscape HTTP Cookie File\n\
+    ///      #HttpOnly_example.com\tFALSE\t/\tTRUE\t0\tid\ta3fWa\n",
+    ///     jar.to_netscape()
+    /// );
+    /// ```
+    pub fn to_netscape(&self) -> String {
+        let mut output = String::from(netscape::HEADER);
+        output.push('\n');
+
+        for cookie in self.cookies.iter() {
+            netscape::write_line(&mut output, cookie);
+        }
+
+        output
+    }
+
+    /// Gets the cookies which should be sent with an HTTP request to `request_url`,
+    /// as described in [RFC 6265, Section 5.4](https://tools.ietf.org/html/rfc6265.html#section-5.4).
+    ///
+    /// Cookies with longer paths come first, and cookies with paths of the same length
+    /// are ordered by their creation time. The last-access time of each cookie is updated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::CookieJar;
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.store("lang=en; Domain=example.com", "https://www.example.com/").unwrap();
+    ///
+    /// let cookies = jar.cookies("https://docs.example.com/").unwrap();
+    /// assert_eq!("lang", cookies[0].get_name());
+    /// assert_eq!("en", cookies[0].get_value());
+    /// ```
+    pub fn cookies(&mut self, request_url: &str) -> Result<Vec<Cookie<'_>>, Error> {
+        self.cookies_at(request_url, SystemTime::now(), CookieApi::Http)
+    }
+
+    /// Same as [`cookies`](CookieJar::cookies), but uses `now` as the current time,
+    /// and leaves out `HttpOnly` cookies unless `api` is [`CookieApi::Http`].
+    pub fn cookies_at(
+        &mut self,
+        request_url: &str,
+        now: SystemTime,
+        api: CookieApi,
+    ) -> Result<Vec<Cookie<'_>>, Error> {
+        let request_url = parse_request_url(request_url)?;
+
+        let mut indices = self
+            .cookies
+            .iter()
+            .enumerate()
+            .filter(|(_, cookie)| cookie.is_retrievable(&request_url, now, api))
+            .map(|(idx, _)| idx)
+            .collect::<Vec<usize>>();
+
+        indices.sort_by(|a, b| {
+            let (a, b) = (&self.cookies[*a], &self.cookies[*b]);
+            b.path
+                .len()
+                .cmp(&a.path.len())
+                .then(a.creation_time.cmp(&b.creation_time))
+        });
+
+        for idx in indices.iter() {
+            self.cookies[*idx].last_access_time = now;
+        }
+
+        Ok(indices
+            .into_iter()
+            .map(|idx| self.cookies[idx].as_cookie())
+            .collect())
+    }
+
+    /// Gets the value of the `Cookie` header for a request to `request_url`,
+    /// or `None` if there are no cookies to send.
+    ///
+    /// The result can be parsed with [`Cookie::parse`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::CookieJar;
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.store("a=1; Path=/", "https://example.com/").unwrap();
+    /// jar.store("b=2; Path=/docs", "https://example.com/").unwrap();
+    /// jar.store("c=3; Secure", "https://example.com/").unwrap();
+    ///
+    /// assert_eq!(
+    ///     Some("b=2; a=1".to_string()),
+    ///     jar.cookie_header("http://example.com/docs/index.html").unwrap()
+    /// );
+    /// ```
+    pub fn cookie_header(&mut self, request_url: &str) -> Result<Option<String>, Error> {
+        self.cookie_header_at(request_url, SystemTime::now())
+    }
+
+    /// Same as [`cookie_header`](CookieJar::cookie_header), but uses `now` as the current time.
+    pub fn cookie_header_at(
+        &mut self,
+        request_url: &str,
+        now: SystemTime,
+    ) -> Result<Option<String>, Error> {
+        let cookies = self.cookies_at(request_url, now, CookieApi::Http)?;
+        if cookies.is_empty() {
+            Ok(None)
+        } else {
+            Cookie::serialize(&cookies).map(Some)
+        }
+    }
+
+    /// Adds the cookie, replacing the one with the same name, domain and path,
+    /// but keeping its creation time.
+    fn insert(&mut self, mut cookie: StoredCookie) {
+        if let Some(idx) = self.cookies.iter().position(|old| {
+            old.name == cookie.name && old.domain == cookie.domain && old.path == cookie.path
+        }) {
+            cookie.creation_time = self.cookies.remove(idx).creation_time;
+        }
+
+        self.cookies.push(cookie);
+    }
+
+    /// Removes the cookies which have expired by `now`.
+    pub fn remove_expired(&mut self, now: SystemTime) {
+        self.cookies.retain(|cookie| !cookie.is_expired(now));
+    }
+
+    /// Iterates over all stored cookies, including the ones which have expired
+    /// since they were last accessed.
+    pub fn iter(&self) -> impl Iterator<Item = &StoredCookie> {
+        self.cookies.iter()
+    }
+
+    /// The number of stored cookies.
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    /// Whether the jar is empty.
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    #[cfg(feature = "public-suffix")]
+    fn is_public_suffix(&self, domain: &str) -> bool {
+        self.public_suffix_list
+            .as_ref()
+            .is_some_and(|public_suffix_list| public_suffix_list.is_public_suffix(domain))
+    }
+
+    #[cfg(not(feature = "public-suffix"))]
+    fn is_public_suffix(&self, _domain: &str) -> bool {
+        false
+    }
+}
+
+/// A cookie in a [`CookieJar`], with the fields described in
+/// [RFC 6265, Section 5.3](https://tools.ietf.org/html/rfc6265.html#section-5.3).
+///
+/// With the `serde` feature, times are represented as whole seconds since the Unix epoch,
+/// and the expiry time is `null` for session cookies.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StoredCookie {
+    name: String,
+    value: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_time::option"))]
+    expiry_time: Option<SystemTime>,
+    domain: String,
+    path: String,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_time"))]
+    creation_time: SystemTime,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_time"))]
+    last_access_time: SystemTime,
+    persistent: bool,
+    host_only: bool,
+    secure_only: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+}
+
+impl StoredCookie {
+    fn from_set_cookie(
+        set_cookie: &SetCookie,
+        request_url: &RequestUrl,
+        now: SystemTime,
+        domain_is_public_suffix: bool,
+    ) -> Result<StoredCookie, Error> {
+        let (persistent, expiry_time) = match set_cookie.get_max_age() {
+            Some(delta_seconds) if delta_seconds <= 0 => (true, Some(UNIX_EPOCH)),
+            Some(delta_seconds) => (
+                true,
+                Some(
+                    now.checked_add(Duration::from_secs(delta_seconds as u64))
+                        .unwrap_or_else(cookie_date::latest_system_time),
+                ),
+            ),
+            None => match set_cookie.get_expires() {
+                Some(expires) => (true, Some(expires)),
+                None => (false, None),
+            },
+        };
+
+        cookie_serializer::validate_cookie_pair(
+            set_cookie.get_name(),
+            unquote(set_cookie.get_value()).0,
+        )
+        .map_err(|_| StorageError::new(StorageErrorKind::InvalidNameOrValue).into_error())?;
+
+        let (host_only, domain) = match set_cookie.get_domain() {
+            Some(domain) if !domain.is_empty() && domain_is_public_suffix => {
+                if request_url.host() != domain {
+                    return Err(StorageError::new(StorageErrorKind::PublicSuffix).into_error());
+                }
+                (true, domain.to_string())
+            }
+            Some(domain) if !domain.is_empty() => {
+                if !cookie_matching::domain_match(request_url.host(), domain) {
+                    return Err(StorageError::new(StorageErrorKind::DomainMismatch).into_error());
+                }
+                (false, domain.to_string())
+            }
+            _ => (true, request_url.host().to_string()),
+        };
+
+        let path = match set_cookie.get_path() {
+            Some(path) => path,
+            None => cookie_matching::default_path(request_url.path()),
+        };
+
+        Ok(StoredCookie {
+            name: set_cookie.get_name().to_string(),
+            value: set_cookie.get_value().to_string(),
+            expiry_time,
+            domain,
+            path: path.to_string(),
+            creation_time: now,
+            last_access_time: now,
+            persistent,
+            host_only,
+            secure_only: set_cookie.is_secure(),
+            http_only: set_cookie.is_http_only(),
+            same_site: set_cookie.get_same_site(),
+        })
+    }
+
+    fn from_netscape_line(line: &NetscapeLine, now: SystemTime) -> Result<StoredCookie, Error> {
+        cookie_serializer::validate_cookie_pair(line.name, unquote(line.value).0).map_err(
+            |_| NetscapeError::new(line.line, NetscapeErrorKind::InvalidNameOrValue).into_error(),
+        )?;
+
+        Ok(StoredCookie {
+            name: line.name.to_string(),
+            value: line.value.to_string(),
+            expiry_time: line.expiry_time,
+            domain: line.domain.to_ascii_lowercase(),
+            path: line.path.to_string(),
+            creation_time: now,
+            last_access_time: now,
+            persistent: line.expiry_time.is_some(),
+            host_only: !line.include_subdomains,
+            secure_only: line.secure,
+            http_only: line.http_only,
+            same_site: None,
+        })
+    }
+
+    fn is_retrievable(&self, request_url: &RequestUrl, now: SystemTime, api: CookieApi) -> bool {
+        let domain_matches = if self.host_only {
+            request_url.host() == self.domain
+        } else {
+            cookie_matching::domain_match(request_url.host(), &self.domain)
+        };
+
+        domain_matches
+            && cookie_matching::path_match(request_url.path(), &self.path)
+            && (!self.secure_only || request_url.is_secure())
+            && (!self.http_only || api == CookieApi::Http)
+            && !self.is_expired(now)
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        matches!(self.expiry_time, Some(expiry_time) if expiry_time <= now)
+    }
+
+    /// Gets the name and the value of the cookie. A value enclosed in double quotes
+    /// is returned without the quotes, the same way [`Cookie::parse`] would return it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::CookieJar;
+    ///
+    /// let mut jar = CookieJar::new();
+    /// jar.store("name=value", "https://example.com/").unwrap();
+    ///
+    /// let cookie = jar.iter().next().unwrap().as_cookie();
+    /// assert_eq!("name", cookie.get_name());
+    /// assert_eq!("value", cookie.get_value());
+    /// ```
+    pub fn as_cookie(&self) -> Cookie<'_> {
+        let (value, quoted) = unquote(&self.value);
+        Cookie::with_quoted(&self.name, value, quoted)
+    }
+
+    /// Gets the name of the cookie.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the value of the cookie.
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
+    /// Gets the time when the cookie expires, or `None` if it never does.
+    pub fn get_expiry_time(&self) -> Option<SystemTime> {
+        self.expiry_time
+    }
+
+    /// Gets the domain of the cookie.
+    pub fn get_domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// Gets the path of the cookie.
+    pub fn get_path(&self) -> &str {
+        &self.path
+    }
+
+    /// Gets the time when the cookie was first stored.
+    pub fn get_creation_time(&self) -> SystemTime {
+        self.creation_time
+    }
+
+    /// Gets the time when the cookie was last stored or retrieved.
+    pub fn get_last_access_time(&self) -> SystemTime {
+        self.last_access_time
+    }
+
+    /// Whether the cookie had a `Max-Age` or an `Expires` attribute,
+    /// i.e. it is not a session cookie.
+    pub fn is_persistent(&self) -> bool {
+        self.persistent
+    }
+
+    /// Whether the cookie is only sent to the host it was received from,
+    /// as opposed to its subdomains as well.
+    pub fn is_host_only(&self) -> bool {
+        self.host_only
+    }
+
+    /// Whether the cookie is only sent over secure protocols.
+    pub fn is_secure_only(&self) -> bool {
+        self.secure_only
+    }
+
+    /// Whether the cookie is only available to HTTP APIs.
+    pub fn is_http_only(&self) -> bool {
+        self.http_only
+    }
+
+    /// Gets the `SameSite` policy of the cookie.
+    pub fn get_same_site(&self) -> Option<SameSite> {
+        self.same_site
+    }
+}
+
+/// The kind of API that cookies are retrieved for, as described in
+/// [RFC 6265, Section 5.4](https://tools.ietf.org/html/rfc6265.html#section-5.4).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CookieApi {
+    /// An HTTP request, which receives `HttpOnly` cookies as well.
+    Http,
+    /// A non-HTTP API, such as `document.cookie` in browsers.
+    NonHttp,
+}
+
+/// Removes the double quotes around a cookie value, if there are any.
+fn unquote(value: &str) -> (&str, bool) {
+    match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(unquoted) if !unquoted.is_empty() => (unquoted, true),
+        _ => (value, false),
+    }
+}
+
+fn parse_request_url(request_url: &str) -> Result<RequestUrl<'_>, Error> {
+    RequestUrl::parse(request_url)
+        .ok_or_else(|| StorageError::new(StorageErrorKind::InvalidRequestUrl).into_error())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CookieApi, CookieJar};
+    use crate::cookie_date::{latest_system_time, system_time_from_unix_seconds};
+    use crate::{Error, PrefixErrorKind, StorageErrorKind};
+    use std::time::{Duration, SystemTime};
+
+    fn now() -> SystemTime {
+        system_time_from_unix_seconds(1_600_000_000)
+    }
+
+    fn header(jar: &mut CookieJar, request_url: &str) -> Option<String> {
+        jar.cookie_header_at(request_url, now()).unwrap()
+    }
+
+    fn storage_error_kind(result: Result<(), Error>) -> StorageErrorKind {
+        match result {
+            Err(Error::StorageError(err)) => err.kind(),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn host_only() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=b", "http://example.com/", now()).unwrap();
+
+        let cookie = jar.iter().next().unwrap();
+        assert!(cookie.is_host_only());
+        assert_eq!("example.com", cookie.get_domain());
+
+        assert_eq!(
+            Some("a=b".to_string()),
+            header(&mut jar, "http://example.com/")
+        );
+        assert_eq!(None, header(&mut jar, "http://www.example.com/"));
+    }
+
+    #[test]
+    fn domain_attribute() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=b; Domain=.Example.com", "http://www.example.com/", now())
+            .unwrap();
+
+        let cookie = jar.iter().next().unwrap();
+        assert!(!cookie.is_host_only());
+        assert_eq!("example.com", cookie.get_domain());
+
+        assert_eq!(
+            Some("a=b".to_string()),
+            header(&mut jar, "http://example.com/")
+        );
+        assert_eq!(
+            Some("a=b".to_string()),
+            header(&mut jar, "http://docs.example.com/")
+        );
+        assert_eq!(None, header(&mut jar, "http://example.org/"));
+    }
+
+    #[test]
+    fn domain_mismatch() {
+        let mut jar = CookieJar::new();
+
+        assert_eq!(
+            StorageErrorKind::DomainMismatch,
+            storage_error_kind(jar.store_at(
+                "a=b; Domain=docs.example.com",
+                "http://www.example.com/",
+                now()
+            ))
+        );
+        assert!(jar.is_empty());
+    }
+
+    #[test]
+    fn invalid_request_url() {
+        let mut jar = CookieJar::new();
+
+        assert_eq!(
+            StorageErrorKind::InvalidRequestUrl,
+            storage_error_kind(jar.store_at("a=b", "example.com", now()))
+        );
+    }
+
+    #[test]
+    fn invalid_set_cookie() {
+        let mut jar = CookieJar::new();
+
+        assert!(matches!(
+            jar.store_at("a", "http://example.com/", now()),
+            Err(Error::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn default_path() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=b", "http://example.com/docs/index.html", now())
+            .unwrap();
+        jar.store_at("c=d; Path=relative", "http://example.com/x/y", now())
+            .unwrap();
+
+        let paths = jar.iter().map(|c| c.get_path()).collect::<Vec<_>>();
+        assert_eq!(vec!["/docs", "/x"], paths);
+
+        assert_eq!(
+            Some("a=b".to_string()),
+            header(&mut jar, "http://example.com/docs/")
+        );
+        assert_eq!(None, header(&mut jar, "http://example.com/"));
+    }
+
+    #[test]
+    fn url_without_path() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=1; Path=/", "https://example.com", now())
+            .unwrap();
+        jar.store_at("b=2", "https://example.com?q=1", now())
+            .unwrap();
+
+        assert_eq!(
+            vec!["/", "/"],
+            jar.iter().map(|c| c.get_path()).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some("a=1; b=2".to_string()),
+            header(&mut jar, "https://example.com")
+        );
+        assert_eq!(
+            Some("a=1; b=2".to_string()),
+            header(&mut jar, "https://example.com#top")
+        );
+    }
+
+    #[test]
+    fn replaces_existing_cookie() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=1; Path=/", "http://example.com/", now())
+            .unwrap();
+        jar.store_at(
+            "a=2; Path=/",
+            "http://example.com/",
+            now() + Duration::from_secs(10),
+        )
+        .unwrap();
+
+        assert_eq!(1, jar.len());
+        let cookie = jar.iter().next().unwrap();
+        assert_eq!("2", cookie.get_value());
+        assert_eq!(now(), cookie.get_creation_time());
+        assert_eq!(
+            now() + Duration::from_secs(10),
+            cookie.get_last_access_time()
+        );
+    }
+
+    #[test]
+    fn same_name_different_path() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=1; Path=/", "http://example.com/", now())
+            .unwrap();
+        jar.store_at("a=2; Path=/docs", "http://example.com/", now())
+            .unwrap();
+
+        assert_eq!(2, jar.len());
+    }
+
+    #[test]
+    fn max_age() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=b; Max-Age=60", "http://example.com/", now())
+            .unwrap();
+
+        let cookie = jar.iter().next().unwrap();
+        assert!(cookie.is_persistent());
+        assert_eq!(
+            Some(now() + Duration::from_secs(60)),
+            cookie.get_expiry_time()
+        );
+
+        assert_eq!(
+            Some("a=b".to_string()),
+            jar.cookie_header_at("http://example.com/", now() + Duration::from_secs(59))
+                .unwrap()
+        );
+        assert_eq!(
+            None,
+            jar.cookie_header_at("http://example.com/", now() + Duration::from_secs(60))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn max_age_overflow() {
+        let mut jar = CookieJar::new();
+        jar.store_at(
+            "a=b; Max-Age=9223372036854775807",
+            "http://example.com/",
+            now(),
+        )
+        .unwrap();
+
+        let cookie = jar.iter().next().unwrap();
+        assert!(cookie.is_persistent());
+        assert_eq!(Some(latest_system_time()), cookie.get_expiry_time());
+        assert!(cookie.get_expiry_time().unwrap() > now() + Duration::from_secs(1 << 40));
+        assert_eq!(
+            Some("a=b".to_string()),
+            header(&mut jar, "http://example.com/")
+        );
+        assert!(!jar.to_netscape().contains("\t0\ta\tb"));
+    }
+
+    #[test]
+    fn max_age_takes_precedence_over_expires() {
+        let mut jar = CookieJar::new();
+        jar.store_at(
+            "a=b; Max-Age=60; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+            "http://example.com/",
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            Some(now() + Duration::from_secs(60)),
+            jar.iter().next().unwrap().get_expiry_time()
+        );
+    }
+
+    #[test]
+    fn expires() {
+        let mut jar = CookieJar::new();
+        jar.store_at(
+            "a=b; Expires=Wed, 21 Oct 2099 07:28:00 GMT",
+            "http://example.com/",
+            now(),
+        )
+        .unwrap();
+
+        let cookie = jar.iter().next().unwrap();
+        assert!(cookie.is_persistent());
+        assert_eq!(
+            Some(system_time_from_unix_seconds(4_096_250_880)),
+            cookie.get_expiry_time()
+        );
+    }
+
+    #[test]
+    fn session_cookie() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=b", "http://example.com/", now()).unwrap();
+
+        let cookie = jar.iter().next().unwrap();
+        assert!(!cookie.is_persistent());
+        assert_eq!(None, cookie.get_expiry_time());
+    }
+
+    #[test]
+    fn expired_cookie_deletes_existing_cookie() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=b; Path=/", "http://example.com/", now())
+            .unwrap();
+        jar.store_at("a=b; Path=/; Max-Age=0", "http://example.com/", now())
+            .unwrap();
+
+        assert!(jar.is_empty());
+    }
+
+    #[test]
+    fn flags() {
+        let mut jar = CookieJar::new();
+        jar.store_at(
+            "a=b; Secure; HttpOnly; SameSite=Lax",
+            "https://example.com/",
+            now(),
+        )
+        .unwrap();
+
+        let cookie = jar.iter().next().unwrap();
+        assert!(cookie.is_secure_only());
+        assert!(cookie.is_http_only());
+        assert_eq!(Some(crate::SameSite::Lax), cookie.get_same_site());
+    }
+
+    #[test]
+    fn ordering() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=1; Path=/", "http://example.com/", now())
+            .unwrap();
+        jar.store_at(
+            "b=2; Path=/docs/web",
+            "http://example.com/",
+            now() + Duration::from_secs(1),
+        )
+        .unwrap();
+        jar.store_at(
+            "c=3; Path=/docs",
+            "http://example.com/",
+            now() + Duration::from_secs(2),
+        )
+        .unwrap();
+        jar.store_at(
+            "d=4; Path=/",
+            "http://example.com/",
+            now() - Duration::from_secs(1),
+        )
+        .unwrap();
+
+        assert_eq!(
+            Some("b=2; c=3; d=4; a=1".to_string()),
+            header(&mut jar, "http://example.com/docs/web/index.html")
+        );
+    }
+
+    #[test]
+    fn updates_last_access_time() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=1; Path=/docs", "http://example.com/", now())
+            .unwrap();
+        jar.store_at("b=2; Path=/other", "http://example.com/", now())
+            .unwrap();
+
+        let later = now() + Duration::from_secs(30);
+        jar.cookie_header_at("http://example.com/docs", later)
+            .unwrap();
+
+        let access_times = jar
+            .iter()
+            .map(|c| c.get_last_access_time())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![later, now()], access_times);
+    }
+
+    #[test]
+    fn secure_only() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=1; Secure", "https://example.com/", now())
+            .unwrap();
+        jar.store_at("b=2", "https://example.com/", now()).unwrap();
+
+        assert_eq!(
+            Some("a=1; b=2".to_string()),
+            header(&mut jar, "https://example.com/")
+        );
+        assert_eq!(
+            Some("a=1; b=2".to_string()),
+            header(&mut jar, "WSS://example.com/")
+        );
+        assert_eq!(
+            Some("b=2".to_string()),
+            header(&mut jar, "http://example.com/")
+        );
+    }
+
+    #[test]
+    fn http_only() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=1; HttpOnly", "http://example.com/", now())
+            .unwrap();
+        jar.store_at("b=2", "http://example.com/", now()).unwrap();
+
+        let names = |jar: &mut CookieJar, api| {
+            jar.cookies_at("http://example.com/", now(), api)
+                .unwrap()
+                .iter()
+                .map(|c| c.get_name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(vec!["a", "b"], names(&mut jar, CookieApi::Http));
+        assert_eq!(vec!["b"], names(&mut jar, CookieApi::NonHttp));
+    }
+
+    #[test]
+    fn quoted_value_round_trip() {
+        let mut jar = CookieJar::new();
+        jar.store_at("a=\"b=c\"", "http://example.com/", now())
+            .unwrap();
+
+        let header = header(&mut jar, "http://example.com/").unwrap();
+        assert_eq!("a=\"b=c\"", header);
+
+        let cookies = crate::Cookie::parse(&header).unwrap();
+        assert_eq!("a", cookies[0].get_name());
+        assert_eq!("b=c", cookies[0].get_value());
+    }
+
+    #[test]
+    fn invalid_name_or_value() {
+        let mut jar = CookieJar::new();
+
+        assert_eq!(
+            StorageErrorKind::InvalidNameOrValue,
+            storage_error_kind(jar.store_at("a=b c", "http://example.com/", now()))
+        );
+        assert_eq!(
+            StorageErrorKind::InvalidNameOrValue,
+            storage_error_kind(jar.store_at("a b=c", "http://example.com/", now()))
+        );
+        assert!(jar.is_empty());
+    }
+
+    #[test]
+    fn prefixes() {
+        let mut jar = CookieJar::new();
+        jar.store_at("__Host-id=1; Secure; Path=/", "https://example.com/", now())
+            .unwrap();
+        jar.store_at("__Secure-id=2; Secure", "https://example.com/", now())
+            .unwrap();
+
+        assert_eq!(
+            Some("__Host-id=1; __Secure-id=2".to_string()),
+            header(&mut jar, "https://example.com/")
+        );
+    }
+
+    #[test]
+    fn prefix_requires_secure_request_url() {
+        let mut jar = CookieJar::new();
+
+        for set_cookie in ["__Host-id=1; Secure; Path=/", "__secure-id=1; Secure"] {
+            match jar.store_at(set_cookie, "http://example.com/", now()) {
+                Err(Error::PrefixError(err)) => {
+                    assert_eq!(PrefixErrorKind::SecureRequestUrlRequired, err.kind())
+                }
+                other => panic!("unexpected result: {:?}", other),
+            }
+        }
+        assert!(jar.is_empty());
+    }
+
+    #[test]
+    fn prefix_requirements() {
+        let mut jar = CookieJar::new();
+
+        assert!(matches!(
+            jar.store_at(
+                "__Host-id=1; Secure; Path=/; Domain=example.com",
+                "https://example.com/",
+                now()
+            ),
+            Err(Error::PrefixError(_))
+        ));
+        assert!(jar.is_empty());
+    }
+
+    #[cfg(feature = "public-suffix")]
+    mod public_suffix {
+        use super::{header, now, storage_error_kind};
+        use crate::{CookieJar, PublicSuffixList, StorageErrorKind};
+
+        fn jar() -> CookieJar {
+            CookieJar::with_public_suffix_list(PublicSuffixList::parse("uk\nco.uk\ncom\n"))
+        }
+
+        #[test]
+        fn rejects_public_suffix_domain() {
+            let mut jar = jar();
+
+            assert_eq!(
+                StorageErrorKind::PublicSuffix,
+                storage_error_kind(jar.store_at(
+                    "a=b; Domain=co.uk",
+                    "http://example.co.uk/",
+                    now()
+                ))
+            );
+            assert_eq!(
+                StorageErrorKind::PublicSuffix,
+                storage_error_kind(jar.store_at("a=b; Domain=.COM", "http://example.com/", now()))
+            );
+            assert!(jar.is_empty());
+        }
+
+        #[test]
+        fn public_suffix_host_is_host_only() {
+            let mut jar = jar();
+            jar.store_at("a=b; Domain=co.uk", "http://co.uk/", now())
+                .unwrap();
+
+            let cookie = jar.iter().next().unwrap();
+            assert!(cookie.is_host_only());
+            assert_eq!("co.uk", cookie.get_domain());
+            assert_eq!(None, header(&mut jar, "http://example.co.uk/"));
+        }
+
+        #[test]
+        fn registrable_domain() {
+            let mut jar = jar();
+            jar.store_at(
+                "a=b; Domain=example.co.uk",
+                "http://www.example.co.uk/",
+                now(),
+            )
+            .unwrap();
+
+            assert_eq!(
+                Some("a=b".to_string()),
+                header(&mut jar, "http://docs.example.co.uk/")
+            );
+        }
+
+        #[test]
+        fn without_list() {
+            let mut jar = CookieJar::new();
+            jar.store_at("a=b; Domain=co.uk", "http://example.co.uk/", now())
+                .unwrap();
+
+            assert!(!jar.iter().next().unwrap().is_host_only());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::{header, now};
+        use crate::CookieJar;
+
+        #[cfg(feature = "public-suffix")]
+        #[test]
+        fn public_suffix_list_not_serialized() {
+            use crate::PublicSuffixList;
+
+            let list = || PublicSuffixList::parse("uk\nco.uk\n");
+            let jar = CookieJar::with_public_suffix_list(list());
+            let json = serde_json::to_string(&jar).unwrap();
+
+            let mut loaded: CookieJar = serde_json::from_str(&json).unwrap();
+            loaded
+                .store_at("a=1; Domain=co.uk", "http://example.co.uk/", now())
+                .unwrap();
+            assert_eq!(1, loaded.len());
+
+            let mut loaded: CookieJar = serde_json::from_str(&json).unwrap();
+            loaded.set_public_suffix_list(list());
+            assert!(loaded
+                .store_at("a=1; Domain=co.uk", "http://example.co.uk/", now())
+                .is_err());
+            assert!(loaded.is_empty());
+        }
+
+        #[test]
+        fn round_trip() {
+            let mut jar = CookieJar::new();
+            jar.store_at("session=abc; HttpOnly", "https://example.com/", now())
+                .unwrap();
+            jar.store_at(
+                "lang=en; Max-Age=60; Path=/docs; SameSite=Strict",
+                "https://example.com/",
+                now(),
+            )
+            .unwrap();
+
+            let json = serde_json::to_string(&jar).unwrap();
+            let loaded: CookieJar = serde_json::from_str(&json).unwrap();
+
+            assert_eq!(json, serde_json::to_string(&loaded).unwrap());
+            assert_eq!(
+                header(&mut jar, "https://example.com/docs/"),
+                header(&mut loaded.clone(), "https://example.com/docs/")
+            );
+        }
+
+        #[test]
+        fn expiry_time_representation() {
+            let mut jar = CookieJar::new();
+            jar.store_at("a=1", "https://example.com/", now()).unwrap();
+            jar.store_at("b=2; Max-Age=60", "https://example.com/", now())
+                .unwrap();
+
+            let json = serde_json::to_value(&jar).unwrap();
+            let cookies = json["cookies"].as_array().unwrap();
+
+            assert!(cookies[0]["expiry_time"].is_null());
+            assert_eq!(Some(1_600_000_060), cookies[1]["expiry_time"].as_i64());
+            assert_eq!(Some(1_600_000_000), cookies[1]["creation_time"].as_i64());
+        }
+    }
+}
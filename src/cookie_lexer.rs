@@ -1,23 +1,32 @@
 use std::fmt::{Display, Error as FormatterError, Formatter};
 
-const COOKIE_LEXER_ERROR_DESCRIPTION: &'static str = "Cookie Lexer Error";
+const COOKIE_LEXER_ERROR_DESCRIPTION: &str = "Cookie Lexer Error";
 
-#[derive(Debug)]
-pub struct CookieLexerError;
+/// A character which is not allowed anywhere in the input.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CookieLexerError {
+    offset: usize,
+    character: char,
+}
 
-impl PartialEq<CookieLexerError> for CookieLexerError {
-    fn eq(&self, _other: &CookieLexerError) -> bool {
-        false
+impl CookieLexerError {
+    pub(crate) fn new(offset: usize, character: char) -> CookieLexerError {
+        CookieLexerError { offset, character }
     }
 
-    fn ne(&self, _other: &CookieLexerError) -> bool {
-        false
+    /// The byte offset of the character in the input.
+    pub(crate) fn offset(&self) -> usize {
+        self.offset
     }
 }
 
 impl Display for CookieLexerError {
     fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
-        f.write_str(COOKIE_LEXER_ERROR_DESCRIPTION)
+        f.write_str(COOKIE_LEXER_ERROR_DESCRIPTION)?;
+        f.write_fmt(format_args!(
+            ": unexpected character {:?} at offset {}",
+            self.character, self.offset
+        ))
     }
 }
 
@@ -44,6 +53,7 @@ pub enum CookieToken {
     Whitespace,
     Space,
     DoubleQuote,
+    AvOctets,
 }
 
 impl CookieToken {
@@ -56,6 +66,7 @@ impl CookieToken {
             CookieToken::Whitespace => "CookieToken::Whitespace",
             CookieToken::Space => "CookieToken::Space",
             CookieToken::DoubleQuote => "CookieToken::DoubleQuote",
+            CookieToken::AvOctets => "CookieToken::AvOctets",
         }
     }
 }
@@ -68,7 +79,7 @@ impl Display for CookieToken {
 
 macro_rules! try_str_match {
     ($token:path, $pattern:expr, $data:expr, $cursor:expr) => {{
-        const PATTERN_STR: &'static str = $pattern;
+        const PATTERN_STR: &str = $pattern;
         if matching::is_str_match($data, PATTERN_STR) {
             let token_idx = $cursor;
             let token_end = token_idx + PATTERN_STR.len();
@@ -82,7 +93,7 @@ macro_rules! try_fn_match {
     ($token:path, $fn:path, $data:expr, $cursor:expr) => {{
         let mut is_match = true;
         let mut last_cursor_char: Option<(usize, char)> = None;
-        for (cursor_char_idx, cursor_char) in $data[$cursor..].iter() {
+        for (cursor_char_idx, cursor_char) in $data.iter() {
             if $fn(*cursor_char) {
                 last_cursor_char = Some((*cursor_char_idx, *cursor_char));
             } else {
@@ -107,10 +118,10 @@ macro_rules! try_fn_match {
 
 macro_rules! try_nonrepeating_char_match {
     ($token:path, $chr:expr, $data:expr, $cursor:expr) => {{
-        let (_, char_val) = $data[$cursor];
+        let (_, char_val) = $data[0];
         if char_val == $chr
-            && ($data.len() == $cursor + 1 || {
-                let (_, next_char) = $data[$cursor + 1];
+            && ($data.len() == 1 || {
+                let (_, next_char) = $data[1];
                 next_char != $chr
             })
         {
@@ -122,18 +133,38 @@ macro_rules! try_nonrepeating_char_match {
     };};
 }
 
+#[derive(Clone, Copy, PartialEq)]
+enum LexerMode {
+    Cookie,
+    SetCookie,
+}
+
 pub(crate) struct CookieLexer<'input> {
     cursor: usize,
     data: &'input str,
     char_indices: Vec<(usize, char)>,
+    mode: LexerMode,
 }
 
 impl<'input> CookieLexer<'input> {
     pub fn new(data: &'input str) -> CookieLexer<'input> {
+        CookieLexer::with_mode(data, LexerMode::Cookie)
+    }
+
+    /// Creates a lexer for `Set-Cookie` header values, where the name, the value and
+    /// the attribute values may contain any character other than `;` and control characters,
+    /// as described in [RFC 6265, Section 5.2](https://tools.ietf.org/html/rfc6265.html#section-5.2),
+    /// such as the comma in `Expires` dates or non-ASCII characters.
+    pub fn new_set_cookie(data: &'input str) -> CookieLexer<'input> {
+        CookieLexer::with_mode(data, LexerMode::SetCookie)
+    }
+
+    fn with_mode(data: &'input str, mode: LexerMode) -> CookieLexer<'input> {
         CookieLexer {
             cursor: 0,
-            data: data,
+            data,
             char_indices: data.char_indices().collect(),
+            mode,
         }
     }
 
@@ -141,17 +172,22 @@ impl<'input> CookieLexer<'input> {
         self.data.get(self.cursor..)
     }
 
+    /// The characters from the cursor, which is a byte offset, to the end of the input.
+    fn chars_at_cursor(&self) -> &[(usize, char)] {
+        let idx = self
+            .char_indices
+            .partition_point(|(char_idx, _)| *char_idx < self.cursor);
+        &self.char_indices[idx..]
+    }
+
     fn get_next_token(&mut self) -> Option<Result<(usize, CookieToken, usize), CookieLexerError>> {
-        let cursor_str = match self.substr_at_cursor() {
-            Some(val) => val,
-            None => return None,
-        };
+        let cursor_str = self.substr_at_cursor()?;
 
-        if cursor_str.len() == 0 {
+        if cursor_str.is_empty() {
             return None;
         }
 
-        try_nonrepeating_char_match!(CookieToken::Space, ' ', self.char_indices, self.cursor);
+        try_nonrepeating_char_match!(CookieToken::Space, ' ', self.chars_at_cursor(), self.cursor);
 
         try_str_match!(CookieToken::Equals, "=", cursor_str, self.cursor);
         try_str_match!(CookieToken::Semicolon, ";", cursor_str, self.cursor);
@@ -160,7 +196,7 @@ impl<'input> CookieLexer<'input> {
         try_fn_match!(
             CookieToken::Whitespace,
             matching::is_whitespace_char,
-            self.char_indices,
+            self.chars_at_cursor(),
             self.cursor
         );
 
@@ -171,10 +207,11 @@ impl<'input> CookieLexer<'input> {
         &mut self,
     ) -> Option<Result<(usize, CookieToken, usize), CookieLexerError>> {
         let mut can_be_token = true;
+        let mut can_be_cookie_octets = true;
         let mut token_end_idx = 0_usize;
 
-        for (_, cursor_char) in self.char_indices[self.cursor..].iter() {
-            match CookieLexer::char_token_class(*cursor_char) {
+        for (cursor_char_idx, cursor_char) in self.chars_at_cursor().iter() {
+            match self.char_token_class_in_mode(*cursor_char) {
                 CharTokenClass::TokenOrCookieOctets => {
                     token_end_idx += cursor_char.len_utf8();
                 }
@@ -182,11 +219,16 @@ impl<'input> CookieLexer<'input> {
                     can_be_token = false;
                     token_end_idx += cursor_char.len_utf8();
                 }
+                CharTokenClass::AvOctets => {
+                    can_be_token = false;
+                    can_be_cookie_octets = false;
+                    token_end_idx += cursor_char.len_utf8();
+                }
                 CharTokenClass::None => {
                     if token_end_idx > 0_usize {
                         break;
                     } else {
-                        return None;
+                        return Some(Err(CookieLexerError::new(*cursor_char_idx, *cursor_char)));
                     }
                 }
             };
@@ -195,8 +237,10 @@ impl<'input> CookieLexer<'input> {
         if token_end_idx > 0 {
             let token = if can_be_token {
                 CookieToken::TokenOrCookieOctets
-            } else {
+            } else if can_be_cookie_octets {
                 CookieToken::CookieOctets
+            } else {
+                CookieToken::AvOctets
             };
 
             let token_start = self.cursor;
@@ -209,6 +253,50 @@ impl<'input> CookieLexer<'input> {
         }
     }
 
+    /// Whether `data` is a valid `token`, as used for cookie names.
+    /// Empty names are accepted, as the parser returns them for nameless cookies.
+    pub(crate) fn is_token(data: &str) -> bool {
+        data.chars().all(CookieLexer::is_token_char)
+    }
+
+    /// Whether `data` only consists of `cookie-octet` characters, as used for cookie values.
+    pub(crate) fn is_cookie_octets(data: &str) -> bool {
+        data.chars()
+            .all(|c| c == '=' || CookieLexer::is_cookie_octet_char(c))
+    }
+
+    /// Whether `c` is a `token` character.
+    pub(crate) fn is_token_char(c: char) -> bool {
+        matches!(
+            CookieLexer::char_token_class(c),
+            CharTokenClass::TokenOrCookieOctets
+        )
+    }
+
+    /// Whether `c` is a `cookie-octet` character, other than `=`.
+    pub(crate) fn is_cookie_octet_char(c: char) -> bool {
+        matches!(
+            CookieLexer::char_token_class(c),
+            CharTokenClass::TokenOrCookieOctets | CharTokenClass::CookieOctets
+        )
+    }
+
+    /// Whether `c` is a whitespace character of `OWS`.
+    pub(crate) fn is_whitespace_char(c: char) -> bool {
+        matching::is_whitespace_char(c)
+    }
+
+    fn char_token_class_in_mode(&self, c: char) -> CharTokenClass {
+        match CookieLexer::char_token_class(c) {
+            CharTokenClass::None
+                if self.mode == LexerMode::SetCookie && matching::is_av_only_char(c) =>
+            {
+                CharTokenClass::AvOctets
+            }
+            class => class,
+        }
+    }
+
     fn char_token_class(c: char) -> CharTokenClass {
         match c {
             '\x21'
@@ -250,6 +338,7 @@ enum CharTokenClass {
     None,
     CookieOctets,
     TokenOrCookieOctets,
+    AvOctets,
 }
 
 impl<'input> Iterator for CookieLexer<'input> {
@@ -266,10 +355,13 @@ mod matching {
     }
 
     pub fn is_whitespace_char(c: char) -> bool {
-        match c {
-            '\x09' | '\x20' => true,
-            _ => false,
-        }
+        matches!(c, '\x09' | '\x20')
+    }
+
+    /// Characters allowed in `Set-Cookie` headers that are neither `token` nor `cookie-octet`
+    /// characters, and are not otherwise significant to the grammar.
+    pub fn is_av_only_char(c: char) -> bool {
+        matches!(c, '\x2c' | '\x5c') || !c.is_ascii()
     }
 
     #[cfg(test)]
@@ -309,7 +401,7 @@ mod matching {
 mod tests {
     #[cfg(test)]
     mod get_next_token {
-        use super::super::{CookieLexer, CookieToken};
+        use super::super::{CookieLexer, CookieLexerError, CookieToken};
 
         #[test]
         fn equals() {
@@ -452,5 +544,111 @@ mod tests {
 
             assert_eq!(Some(Ok((2, CookieToken::Space, 3))), lexer.get_next_token());
         }
+
+        #[test]
+        fn av_octets() {
+            let mut lexer = CookieLexer::new_set_cookie("Wed, 21");
+
+            assert_eq!(
+                Some(Ok((0, CookieToken::AvOctets, 4))),
+                lexer.get_next_token()
+            );
+
+            assert_eq!(Some(Ok((4, CookieToken::Space, 5))), lexer.get_next_token());
+
+            assert_eq!(
+                Some(Ok((5, CookieToken::TokenOrCookieOctets, 7))),
+                lexer.get_next_token()
+            );
+        }
+
+        #[test]
+        fn av_octets_not_in_cookie_mode() {
+            let mut lexer = CookieLexer::new("Wed, 21");
+
+            assert_eq!(
+                Some(Ok((0, CookieToken::TokenOrCookieOctets, 3))),
+                lexer.get_next_token()
+            );
+            assert_eq!(
+                Some(Err(CookieLexerError::new(3, ','))),
+                lexer.get_next_token()
+            );
+        }
+
+        #[test]
+        fn disallowed_character() {
+            let mut lexer = CookieLexer::new("a=b\\c");
+
+            assert_eq!(
+                Some(Ok((0, CookieToken::TokenOrCookieOctets, 1))),
+                lexer.get_next_token()
+            );
+            assert_eq!(
+                Some(Ok((1, CookieToken::Equals, 2))),
+                lexer.get_next_token()
+            );
+            assert_eq!(
+                Some(Ok((2, CookieToken::TokenOrCookieOctets, 3))),
+                lexer.get_next_token()
+            );
+            assert_eq!(
+                Some(Err(CookieLexerError::new(3, '\\'))),
+                lexer.get_next_token()
+            );
+        }
+
+        #[test]
+        fn control_character() {
+            assert_eq!(
+                Some(Err(CookieLexerError::new(0, '\x7f'))),
+                CookieLexer::new("\x7fa").get_next_token()
+            );
+        }
+
+        #[test]
+        fn non_ascii_character() {
+            let mut lexer = CookieLexer::new("a=\u{e9}");
+            lexer.get_next_token();
+            lexer.get_next_token();
+
+            assert_eq!(
+                Some(Err(CookieLexerError::new(2, '\u{e9}'))),
+                lexer.get_next_token()
+            );
+        }
+    }
+
+    mod char_classes {
+        use super::super::CookieLexer;
+
+        #[test]
+        fn token() {
+            assert!(CookieLexer::is_token("session_id-2"));
+            assert!(CookieLexer::is_token(""));
+        }
+
+        #[test]
+        fn not_token() {
+            assert!(!CookieLexer::is_token("a(b)"));
+            assert!(!CookieLexer::is_token("a=b"));
+            assert!(!CookieLexer::is_token("a b"));
+        }
+
+        #[test]
+        fn cookie_octets() {
+            assert!(CookieLexer::is_cookie_octets("a(b)=c/d"));
+            assert!(CookieLexer::is_cookie_octets(""));
+        }
+
+        #[test]
+        fn not_cookie_octets() {
+            assert!(!CookieLexer::is_cookie_octets("a,b"));
+            assert!(!CookieLexer::is_cookie_octets("a;b"));
+            assert!(!CookieLexer::is_cookie_octets("a b"));
+            assert!(!CookieLexer::is_cookie_octets("\"ab\""));
+            assert!(!CookieLexer::is_cookie_octets("a\\b"));
+            assert!(!CookieLexer::is_cookie_octets("\u{e9}"));
+        }
     }
 }
@@ -0,0 +1,268 @@
+use super::cookie::DuplicateError;
+use super::{Cookie, Error};
+use std::collections::HashMap;
+
+/// Decides which cookie [`CookieMap::get`] returns when a name appears more than once.
+///
+/// User agents send the cookies with the longest paths first, as described in
+/// [RFC 6265, Section 5.4](https://tools.ietf.org/html/rfc6265.html#section-5.4),
+/// so the first cookie is usually the most specific one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// The first cookie with the name is returned.
+    #[default]
+    FirstWins,
+    /// The last cookie with the name is returned.
+    LastWins,
+    /// Creating the map fails with [`Error::DuplicateError`].
+    Error,
+}
+
+/// The cookies of a `Cookie` header, looked up by name.
+///
+/// Every cookie is kept in the order of the input, including the ones with duplicate names.
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::CookieMap;
+///
+/// let cookies = CookieMap::parse("session=abc; theme=dark").unwrap();
+///
+/// assert_eq!("abc", cookies.get("session").unwrap().get_value());
+/// assert!(cookies.contains("theme"));
+/// assert!(cookies.get("lang").is_none());
+/// ```
+#[derive(Clone, Debug)]
+pub struct CookieMap<'a> {
+    cookies: Vec<Cookie<'a>>,
+    index: HashMap<&'a str, usize>,
+    policy: DuplicatePolicy,
+}
+
+impl<'a> CookieMap<'a> {
+    /// Creates a map from the given cookies, e.g. the result of [`Cookie::parse`].
+    ///
+    /// Fails with [`Error::DuplicateError`] only if the policy is [`DuplicatePolicy::Error`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, CookieMap, DuplicatePolicy, Error};
+    ///
+    /// let parsed_cookies = Cookie::parse("a=1; b=2; a=3").unwrap();
+    ///
+    /// let cookies = CookieMap::new(parsed_cookies.clone(), DuplicatePolicy::LastWins).unwrap();
+    /// assert_eq!("3", cookies.get("a").unwrap().get_value());
+    ///
+    /// match CookieMap::new(parsed_cookies, DuplicatePolicy::Error) {
+    ///     Err(Error::DuplicateError(err)) => assert_eq!("a", err.name()),
+    ///     _ => panic!("the duplicate should have been rejected"),
+    /// }
+    /// ```
+    pub fn new(cookies: Vec<Cookie<'a>>, policy: DuplicatePolicy) -> Result<CookieMap<'a>, Error> {
+        let mut index = HashMap::with_capacity(cookies.len());
+
+        for (idx, cookie) in cookies.iter().enumerate() {
+            match policy {
+                DuplicatePolicy::FirstWins => {
+                    index.entry(cookie.get_name()).or_insert(idx);
+                }
+                DuplicatePolicy::LastWins => {
+                    index.insert(cookie.get_name(), idx);
+                }
+                DuplicatePolicy::Error => {
+                    if index.insert(cookie.get_name(), idx).is_some() {
+                        return Err(DuplicateError::new(cookie.get_name()).into_error());
+                    }
+                }
+            }
+        }
+
+        Ok(CookieMap {
+            cookies,
+            index,
+            policy,
+        })
+    }
+
+    /// Parses the value of a `Cookie` header with [`Cookie::parse`], and creates a map
+    /// with the [`DuplicatePolicy::FirstWins`] policy.
+    pub fn parse(input: &'a str) -> Result<CookieMap<'a>, Error> {
+        CookieMap::new(Cookie::parse(input)?, DuplicatePolicy::FirstWins)
+    }
+
+    /// Gets the cookie with the given name, chosen by the duplicate policy
+    /// if there is more than one.
+    pub fn get(&self, name: &str) -> Option<&Cookie<'a>> {
+        self.index.get(name).map(|idx| &self.cookies[*idx])
+    }
+
+    /// Gets every cookie with the given name, in the order of the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::CookieMap;
+    ///
+    /// let cookies = CookieMap::parse("a=1; b=2; a=3").unwrap();
+    ///
+    /// assert_eq!(
+    ///     vec!["1", "3"],
+    ///     cookies.get_all("a").map(|c| c.get_value()).collect::<Vec<_>>()
+    /// );
+    /// ```
+    pub fn get_all<'m>(&'m self, name: &'m str) -> impl Iterator<Item = &'m Cookie<'a>> + 'm {
+        self.cookies
+            .iter()
+            .filter(move |cookie| cookie.get_name() == name)
+    }
+
+    /// Whether there is a cookie with the given name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.index.contains_key(name)
+    }
+
+    /// Gets the policy which decides which cookie [`get`](CookieMap::get) returns.
+    pub fn policy(&self) -> DuplicatePolicy {
+        self.policy
+    }
+
+    /// Iterates over every cookie, in the order of the input.
+    pub fn iter(&self) -> std::slice::Iter<'_, Cookie<'a>> {
+        self.cookies.iter()
+    }
+
+    /// Gets every cookie, in the order of the input.
+    pub fn as_slice(&self) -> &[Cookie<'a>] {
+        &self.cookies
+    }
+
+    /// Gets the number of cookies, including the ones with duplicate names.
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    /// Whether there are no cookies.
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    /// Takes every cookie, in the order of the input.
+    pub fn into_cookies(self) -> Vec<Cookie<'a>> {
+        self.cookies
+    }
+}
+
+impl<'m, 'a> IntoIterator for &'m CookieMap<'a> {
+    type Item = &'m Cookie<'a>;
+    type IntoIter = std::slice::Iter<'m, Cookie<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for CookieMap<'a> {
+    type Item = Cookie<'a>;
+    type IntoIter = std::vec::IntoIter<Cookie<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cookies.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CookieMap, DuplicatePolicy};
+    use crate::{Cookie, Error};
+
+    const COOKIE_STR: &str = "session=abc; a=1; theme=dark; a=2; =nokey; a=3";
+
+    fn map(policy: DuplicatePolicy) -> Result<CookieMap<'static>, Error> {
+        CookieMap::new(Cookie::parse(COOKIE_STR).unwrap(), policy)
+    }
+
+    #[test]
+    fn get() {
+        let cookies = map(DuplicatePolicy::FirstWins).unwrap();
+
+        assert_eq!("abc", cookies.get("session").unwrap().get_value());
+        assert_eq!("dark", cookies.get("theme").unwrap().get_value());
+        assert_eq!("nokey", cookies.get("").unwrap().get_value());
+        assert!(cookies.get("Session").is_none());
+        assert!(cookies.contains("a"));
+        assert!(!cookies.contains("b"));
+    }
+
+    #[test]
+    fn first_wins() {
+        let cookies = map(DuplicatePolicy::FirstWins).unwrap();
+
+        assert_eq!(DuplicatePolicy::FirstWins, cookies.policy());
+        assert_eq!("1", cookies.get("a").unwrap().get_value());
+    }
+
+    #[test]
+    fn last_wins() {
+        let cookies = map(DuplicatePolicy::LastWins).unwrap();
+
+        assert_eq!("3", cookies.get("a").unwrap().get_value());
+        assert_eq!("abc", cookies.get("session").unwrap().get_value());
+    }
+
+    #[test]
+    fn duplicate_error() {
+        match map(DuplicatePolicy::Error) {
+            Err(Error::DuplicateError(err)) => assert_eq!("a", err.name()),
+            other => panic!("result: {:?}", other),
+        }
+
+        assert!(CookieMap::new(Cookie::parse("a=1; b=2").unwrap(), DuplicatePolicy::Error).is_ok());
+    }
+
+    #[test]
+    fn get_all() {
+        let cookies = map(DuplicatePolicy::LastWins).unwrap();
+
+        assert_eq!(
+            vec!["1", "2", "3"],
+            cookies
+                .get_all("a")
+                .map(|cookie| cookie.get_value())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, cookies.get_all("b").count());
+    }
+
+    #[test]
+    fn original_order() {
+        let cookies = map(DuplicatePolicy::FirstWins).unwrap();
+
+        assert_eq!(6, cookies.len());
+        assert_eq!(
+            vec!["session", "a", "theme", "a", "", "a"],
+            cookies
+                .iter()
+                .map(|cookie| cookie.get_name())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Cookie::parse(COOKIE_STR).unwrap(), cookies.into_cookies());
+    }
+
+    #[test]
+    fn empty() {
+        let cookies = CookieMap::new(Vec::new(), DuplicatePolicy::default()).unwrap();
+
+        assert!(cookies.is_empty());
+        assert!(cookies.get("a").is_none());
+    }
+
+    #[test]
+    fn parse_error() {
+        assert!(matches!(
+            CookieMap::parse("a=b c"),
+            Err(Error::ParseError(_))
+        ));
+    }
+}
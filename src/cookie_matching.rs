@@ -0,0 +1,210 @@
+use std::net::IpAddr;
+
+/// Whether `string` domain-matches `domain_string`, as described in
+/// [RFC 6265, Section 5.1.3](https://tools.ietf.org/html/rfc6265.html#section-5.1.3).
+///
+/// Both arguments are compared case-insensitively. A string which is an IP address
+/// only matches an identical domain string, and no normalization, such as the removal
+/// of a leading or trailing dot, takes place.
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::domain_match;
+///
+/// assert!(domain_match("www.Example.com", "example.com"));
+/// assert!(!domain_match("www.example.com", "ample.com"));
+/// assert!(!domain_match("192.168.0.1", "168.0.1"));
+/// ```
+pub fn domain_match(string: &str, domain_string: &str) -> bool {
+    if string.eq_ignore_ascii_case(domain_string) {
+        return true;
+    }
+
+    let string_bytes = string.as_bytes();
+    let suffix_start = match string_bytes.len().checked_sub(domain_string.len()) {
+        Some(suffix_start) if suffix_start > 0 && !domain_string.is_empty() => suffix_start,
+        _ => return false,
+    };
+
+    string_bytes[suffix_start..].eq_ignore_ascii_case(domain_string.as_bytes())
+        && string_bytes[suffix_start - 1] == b'.'
+        && !is_ip_address(string)
+}
+
+/// Whether `request_path` path-matches `cookie_path`, as described in
+/// [RFC 6265, Section 5.1.4](https://tools.ietf.org/html/rfc6265.html#section-5.1.4).
+///
+/// Paths are compared case-sensitively, and a cookie path only matches
+/// at a `/` boundary of the request path.
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::path_match;
+///
+/// assert!(path_match("/docs/web", "/docs"));
+/// assert!(path_match("/docs/web", "/docs/"));
+/// assert!(!path_match("/docsets", "/docs"));
+/// ```
+pub fn path_match(request_path: &str, cookie_path: &str) -> bool {
+    match request_path.strip_prefix(cookie_path) {
+        Some(rest) => rest.is_empty() || cookie_path.ends_with('/') || rest.starts_with('/'),
+        None => false,
+    }
+}
+
+/// Computes the default path of a cookie from the path of the request URI, as described in
+/// [RFC 6265, Section 5.1.4](https://tools.ietf.org/html/rfc6265.html#section-5.1.4).
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::default_path;
+///
+/// assert_eq!("/docs", default_path("/docs/index.html"));
+/// assert_eq!("/", default_path("/index.html"));
+/// assert_eq!("/", default_path("relative"));
+/// ```
+pub fn default_path(uri_path: &str) -> &str {
+    if !uri_path.starts_with('/') {
+        return "/";
+    }
+
+    match uri_path.rfind('/') {
+        Some(0) | None => "/",
+        Some(idx) => &uri_path[..idx],
+    }
+}
+
+fn is_ip_address(host: &str) -> bool {
+    let host = host
+        .strip_prefix('[')
+        .and_then(|host| host.strip_suffix(']'))
+        .unwrap_or(host);
+    host.parse::<IpAddr>().is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default_path, domain_match, path_match};
+
+    #[test]
+    fn domain_match_identical() {
+        assert!(domain_match("example.com", "example.com"));
+        assert!(domain_match("localhost", "localhost"));
+    }
+
+    #[test]
+    fn domain_match_case_insensitive() {
+        assert!(domain_match("EXAMPLE.com", "example.COM"));
+        assert!(domain_match("WWW.Example.Com", "example.com"));
+        assert!(domain_match("www.example.com", "EXAMPLE.COM"));
+    }
+
+    #[test]
+    fn domain_match_subdomain() {
+        assert!(domain_match("www.example.com", "example.com"));
+        assert!(domain_match("a.b.example.com", "example.com"));
+        assert!(domain_match("a.b.example.com", "b.example.com"));
+        assert!(!domain_match("www.example.com", "ample.com"));
+        assert!(!domain_match("wwwexample.com", "example.com"));
+    }
+
+    #[test]
+    fn domain_match_not_superdomain() {
+        assert!(!domain_match("example.com", "www.example.com"));
+        assert!(!domain_match("com", "example.com"));
+    }
+
+    #[test]
+    fn domain_match_leading_dot() {
+        assert!(!domain_match("www.example.com", ".example.com"));
+        assert!(domain_match(".example.com", ".example.com"));
+    }
+
+    #[test]
+    fn domain_match_trailing_dot() {
+        assert!(!domain_match("example.com.", "example.com"));
+        assert!(!domain_match("example.com", "example.com."));
+        assert!(domain_match("www.example.com.", "example.com."));
+        assert!(domain_match("example.com.", "example.com."));
+    }
+
+    #[test]
+    fn domain_match_empty() {
+        assert!(domain_match("", ""));
+        assert!(!domain_match("example.com.", ""));
+        assert!(!domain_match("", "example.com"));
+    }
+
+    #[test]
+    fn domain_match_ipv4_address() {
+        assert!(domain_match("192.168.0.1", "192.168.0.1"));
+        assert!(!domain_match("192.168.0.1", "168.0.1"));
+        assert!(!domain_match("192.168.0.1", "0.1"));
+    }
+
+    #[test]
+    fn domain_match_ipv6_address() {
+        assert!(domain_match("[::1]", "[::1]"));
+        assert!(domain_match("::1", "::1"));
+        assert!(!domain_match("[2001:db8::1]", "1]"));
+        assert!(!domain_match("2001:db8::1.2.3.4", "2.3.4"));
+    }
+
+    #[test]
+    fn domain_match_numeric_host_name() {
+        assert!(domain_match("1.example.com", "example.com"));
+        assert!(domain_match("1.2.3.4.5", "4.5"));
+    }
+
+    #[test]
+    fn path_match_identical() {
+        assert!(path_match("/", "/"));
+        assert!(path_match("/docs", "/docs"));
+        assert!(path_match("/docs/", "/docs/"));
+    }
+
+    #[test]
+    fn path_match_prefix() {
+        assert!(path_match("/docs", "/"));
+        assert!(path_match("/docs/web", "/docs"));
+        assert!(path_match("/docs/web", "/docs/"));
+        assert!(path_match("/docs/", "/docs"));
+    }
+
+    #[test]
+    fn path_match_slash_boundary() {
+        assert!(!path_match("/docsets", "/docs"));
+        assert!(!path_match("/docs", "/docs/"));
+        assert!(!path_match("/doc", "/docs"));
+        assert!(!path_match("/", "/docs"));
+    }
+
+    #[test]
+    fn path_match_case_sensitive() {
+        assert!(!path_match("/Docs", "/docs"));
+        assert!(!path_match("/docs", "/DOCS"));
+    }
+
+    #[test]
+    fn path_match_empty() {
+        assert!(!path_match("", "/"));
+        assert!(path_match("/docs", ""));
+    }
+
+    #[test]
+    fn default_path_directory() {
+        assert_eq!("/docs", default_path("/docs/index.html"));
+        assert_eq!("/docs/web", default_path("/docs/web/"));
+        assert_eq!("/", default_path("/index.html"));
+        assert_eq!("/", default_path("/"));
+    }
+
+    #[test]
+    fn default_path_not_absolute() {
+        assert_eq!("/", default_path(""));
+        assert_eq!("/", default_path("docs/index.html"));
+    }
+}
@@ -0,0 +1,133 @@
+use super::cookie::{SerializeError, SerializeErrorKind};
+use super::{Cookie, CookieLexer, Error};
+
+pub(crate) fn serialize_cookies(cookies: &[Cookie]) -> Result<String, Error> {
+    let mut output = String::new();
+
+    for (idx, cookie) in cookies.iter().enumerate() {
+        if idx > 0 {
+            output.push_str("; ");
+        }
+
+        write_cookie_pair(
+            &mut output,
+            cookie.get_name(),
+            cookie.get_value(),
+            cookie.is_quoted(),
+        )?;
+    }
+
+    Ok(output)
+}
+
+/// Writes `name=value`, enclosing the value in double quotes if `quoted` is set
+/// and the value is not empty.
+pub(crate) fn write_cookie_pair(
+    output: &mut String,
+    name: &str,
+    value: &str,
+    quoted: bool,
+) -> Result<(), Error> {
+    validate_cookie_pair(name, value)?;
+
+    output.push_str(name);
+    output.push('=');
+
+    if quoted && !value.is_empty() {
+        output.push('"');
+        output.push_str(value);
+        output.push('"');
+    } else {
+        output.push_str(value);
+    }
+
+    Ok(())
+}
+
+/// Checks that `name` is a token and `value` only consists of `cookie-octet` characters.
+/// The name may be empty.
+pub(crate) fn validate_cookie_pair(name: &str, value: &str) -> Result<(), Error> {
+    if !CookieLexer::is_token(name) {
+        return Err(SerializeError::new(SerializeErrorKind::Name).into_error());
+    }
+
+    if !CookieLexer::is_cookie_octets(value) {
+        return Err(SerializeError::new(SerializeErrorKind::Value).into_error());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::serialize_cookies;
+    use crate::Cookie;
+
+    fn round_trip(input: &str) -> String {
+        serialize_cookies(&Cookie::parse(input).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn single_cookie() {
+        assert_eq!("test=1234", round_trip("test=1234"));
+    }
+
+    #[test]
+    fn three_cookies() {
+        assert_eq!(
+            "test1=0x1234; test2=test2; third_val=v4lue",
+            round_trip(" test1=0x1234;test2=test2; third_val=v4lue  ")
+        );
+    }
+
+    #[test]
+    fn quoted_value() {
+        assert_eq!(
+            "quoted_test=\"quotedval\"",
+            round_trip("quoted_test=\"quotedval\"")
+        );
+    }
+
+    #[test]
+    fn equals_in_value() {
+        assert_eq!("test=abc=123", round_trip("test=abc=123"));
+    }
+
+    #[test]
+    fn empty_name() {
+        assert_eq!("=nokey", round_trip("nokey"));
+        assert_eq!("=a=b", round_trip("=a=b"));
+    }
+
+    #[test]
+    fn empty_name_allowed() {
+        assert_eq!(
+            "=value; a=1",
+            serialize_cookies(&[Cookie::new("", "value"), Cookie::new("a", "1")]).unwrap()
+        );
+        assert_eq!("=", serialize_cookies(&[Cookie::new("", "")]).unwrap());
+    }
+
+    #[test]
+    fn empty_value() {
+        assert_eq!("noval=", round_trip("noval="));
+    }
+
+    #[test]
+    fn empty_list() {
+        assert_eq!("", serialize_cookies(&[]).unwrap());
+    }
+
+    #[test]
+    fn invalid_name() {
+        assert!(serialize_cookies(&[Cookie::new("a b", "c")]).is_err());
+        assert!(serialize_cookies(&[Cookie::new("a=", "c")]).is_err());
+    }
+
+    #[test]
+    fn invalid_value() {
+        assert!(serialize_cookies(&[Cookie::new("a", "b;c")]).is_err());
+        assert!(serialize_cookies(&[Cookie::new("a", "b c")]).is_err());
+        assert!(serialize_cookies(&[Cookie::new("a", "\"b\"")]).is_err());
+    }
+}
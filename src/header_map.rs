@@ -0,0 +1,223 @@
+use super::cookie::{ParseError, SerializeError, SerializeErrorKind};
+use super::{Cookie, Error, SetCookie, SetCookieBuilder};
+use http::header::{COOKIE, SET_COOKIE};
+use http::{HeaderMap, HeaderValue};
+
+/// Parses every `Cookie` header of a request with [`Cookie::parse`].
+///
+/// HTTP/2 and HTTP/3 clients may split the cookies into several `Cookie` headers,
+/// which are joined with `"; "` as described in
+/// [RFC 6265bis](https://datatracker.ietf.org/doc/html/draft-ietf-httpbis-rfc6265bis#section-5.8.1).
+/// Each header is parsed separately, so that the cookies still borrow from the header map,
+/// and the offsets of a [`Error::ParseError`] are relative to the header which could not be parsed.
+///
+/// Returns no cookies if there is no `Cookie` header.
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::cookies_from_headers;
+/// use http::header::{HeaderMap, HeaderValue, COOKIE};
+///
+/// let mut headers = HeaderMap::new();
+/// headers.append(COOKIE, HeaderValue::from_static("session=abc"));
+/// headers.append(COOKIE, HeaderValue::from_static("theme=dark; lang=en"));
+///
+/// let cookies = cookies_from_headers(&headers).unwrap();
+///
+/// assert_eq!(
+///     vec!["session", "theme", "lang"],
+///     cookies.iter().map(|c| c.get_name()).collect::<Vec<_>>()
+/// );
+/// ```
+pub fn cookies_from_headers(headers: &HeaderMap) -> Result<Vec<Cookie<'_>>, Error> {
+    let mut cookies = Vec::new();
+
+    for value in headers.get_all(COOKIE) {
+        cookies.extend(Cookie::parse(header_str(value)?)?);
+    }
+
+    Ok(cookies)
+}
+
+/// Builds a `Set-Cookie` header with [`SetCookieBuilder::build`], and appends it to the
+/// headers of a response, keeping any `Set-Cookie` headers which are already there.
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::{append_set_cookie, SetCookieBuilder};
+/// use http::header::{HeaderMap, SET_COOKIE};
+///
+/// let mut headers = HeaderMap::new();
+/// append_set_cookie(&mut headers, &SetCookieBuilder::new("session", "abc").http_only(true))
+///     .unwrap();
+/// append_set_cookie(&mut headers, &SetCookieBuilder::new("theme", "dark")).unwrap();
+///
+/// assert_eq!(
+///     vec!["session=abc; HttpOnly", "theme=dark"],
+///     headers.get_all(SET_COOKIE).iter().collect::<Vec<_>>()
+/// );
+/// ```
+pub fn append_set_cookie(headers: &mut HeaderMap, builder: &SetCookieBuilder) -> Result<(), Error> {
+    let value = HeaderValue::try_from(builder.build()?)
+        .map_err(|_| SerializeError::new(SerializeErrorKind::AttributeValue).into_error())?;
+
+    headers.append(SET_COOKIE, value);
+    Ok(())
+}
+
+/// Parses every `Set-Cookie` header of a response with [`SetCookie::parse`],
+/// in the order of the headers.
+///
+/// Fails if any of the headers cannot be parsed.
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::set_cookies_from_headers;
+/// use http::header::{HeaderMap, HeaderValue, SET_COOKIE};
+///
+/// let mut headers = HeaderMap::new();
+/// headers.append(SET_COOKIE, HeaderValue::from_static("session=abc; HttpOnly"));
+/// headers.append(SET_COOKIE, HeaderValue::from_static("theme=dark; Max-Age=60"));
+///
+/// let set_cookies = set_cookies_from_headers(&headers).unwrap();
+///
+/// assert_eq!("session", set_cookies[0].get_name());
+/// assert_eq!(Some(60), set_cookies[1].get_max_age());
+/// ```
+pub fn set_cookies_from_headers(headers: &HeaderMap) -> Result<Vec<SetCookie<'_>>, Error> {
+    headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .map(|value| SetCookie::parse(header_str(value)?))
+        .collect()
+}
+
+/// Gets the header value as a string, or fails at its first byte which is not visible ASCII.
+fn header_str(value: &HeaderValue) -> Result<&str, Error> {
+    value.to_str().map_err(|_| {
+        let input = value.as_bytes();
+        let offset = input
+            .iter()
+            .position(|b| !(*b == b'\t' || (b' '..=b'~').contains(b)))
+            .unwrap_or(input.len());
+
+        ParseError::new(input, offset, Vec::new()).into_error()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_set_cookie, cookies_from_headers, set_cookies_from_headers};
+    use crate::{Error, ParseErrorKind, SetCookieAttribute, SetCookieBuilder};
+    use http::header::{HeaderMap, HeaderValue, COOKIE, SET_COOKIE};
+
+    fn headers(name: http::HeaderName, values: &[&'static str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(name.clone(), HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn cookies_joined() {
+        let headers = headers(COOKIE, &["a=1; b=2", "c=3", "a=4"]);
+        let cookies = cookies_from_headers(&headers).unwrap();
+
+        assert_eq!(
+            vec![("a", "1"), ("b", "2"), ("c", "3"), ("a", "4")],
+            cookies
+                .iter()
+                .map(|cookie| (cookie.get_name(), cookie.get_value()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn no_cookies() {
+        let headers = headers(SET_COOKIE, &["a=1"]);
+
+        assert!(cookies_from_headers(&headers).unwrap().is_empty());
+        assert!(set_cookies_from_headers(&HeaderMap::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn cookie_parse_error() {
+        let headers = headers(COOKIE, &["a=1", "b=2 3"]);
+
+        match cookies_from_headers(&headers) {
+            Err(Error::ParseError(err)) => {
+                assert_eq!(4, err.offset());
+                assert_eq!(ParseErrorKind::UnexpectedCharacter('3'), err.kind());
+            }
+            other => panic!("result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn non_ascii_header() {
+        let mut headers = HeaderMap::new();
+        headers.append(COOKIE, HeaderValue::from_bytes(b"a=1; b=\xc3\xa9").unwrap());
+
+        match cookies_from_headers(&headers) {
+            Err(Error::ParseError(err)) => {
+                assert_eq!(7, err.offset());
+                assert_eq!(ParseErrorKind::UnexpectedCharacter('é'), err.kind());
+            }
+            other => panic!("result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn append_and_extract() {
+        let mut headers = headers(SET_COOKIE, &["existing=1"]);
+        append_set_cookie(
+            &mut headers,
+            &SetCookieBuilder::new("id", "a3fWa")
+                .max_age(60)
+                .path("/")
+                .secure(true),
+        )
+        .unwrap();
+
+        let set_cookies = set_cookies_from_headers(&headers).unwrap();
+
+        assert_eq!(2, set_cookies.len());
+        assert_eq!("existing", set_cookies[0].get_name());
+        assert_eq!("id", set_cookies[1].get_name());
+        assert_eq!(
+            &[
+                SetCookieAttribute::MaxAge(60),
+                SetCookieAttribute::Path("/"),
+                SetCookieAttribute::Secure
+            ],
+            set_cookies[1].get_attributes()
+        );
+    }
+
+    #[test]
+    fn append_invalid() {
+        let mut headers = HeaderMap::new();
+
+        assert!(matches!(
+            append_set_cookie(&mut headers, &SetCookieBuilder::new("", "a")),
+            Err(Error::SerializeError(_))
+        ));
+        assert!(headers.is_empty());
+    }
+
+    #[test]
+    fn set_cookie_parse_error() {
+        let headers = headers(SET_COOKIE, &["a=1", "__Host-id=1"]);
+
+        assert!(matches!(
+            set_cookies_from_headers(&headers),
+            Err(Error::PrefixError(_))
+        ));
+    }
+}
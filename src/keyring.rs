@@ -0,0 +1,547 @@
+use super::cookie::{SignatureError, SignatureErrorKind};
+use super::{Cookie, Error, OwnedCookie};
+#[cfg(feature = "private")]
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+#[cfg(feature = "private")]
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::fmt;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// The length of a base64 encoded HMAC-SHA256 tag, without padding.
+const SIGNATURE_LEN: usize = 43;
+
+/// The length of an AES-GCM nonce.
+#[cfg(feature = "private")]
+const NONCE_LEN: usize = 12;
+
+/// The input for deriving encryption keys. As it does not contain `=`, it cannot be
+/// the input of a signature.
+#[cfg(feature = "private")]
+const ENCRYPTION_KEY_LABEL: &[u8] = b"basic-cookies private cookie key";
+
+/// Signs cookie values with HMAC-SHA256, so that changes to them can be detected.
+///
+/// The signature covers both the name and the value, and is prepended to the value,
+/// encoded with the URL-safe base64 alphabet, which only uses characters allowed in cookie values.
+///
+/// Values are signed with a single key, and verified with that key or any of the older keys
+/// added with [`with_verification_key`](Keyring::with_verification_key), so that keys can be rotated
+/// without rejecting the cookies which were signed before.
+///
+/// With the `private` feature, values can also be encrypted, so that they cannot be read by clients.
+///
+/// Keys should be at least 32 random bytes.
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::{Cookie, Keyring};
+///
+/// let keyring = Keyring::new(b"an example key which is long enough".to_vec());
+///
+/// let signed = keyring.sign(&Cookie::new("session", "abc"));
+/// let header = Cookie::serialize(&[signed.as_cookie()]).unwrap();
+///
+/// let parsed_cookies = Cookie::parse(&header).unwrap();
+/// let verified = keyring.verify(&parsed_cookies[0]).unwrap();
+///
+/// assert_eq!("session", verified.get_name());
+/// assert_eq!("abc", verified.get_value());
+/// ```
+#[derive(Clone)]
+pub struct Keyring {
+    signing_key: Vec<u8>,
+    verification_keys: Vec<Vec<u8>>,
+}
+
+impl Keyring {
+    /// Creates a keyring which signs and verifies with the given key.
+    pub fn new<K: Into<Vec<u8>>>(signing_key: K) -> Keyring {
+        Keyring {
+            signing_key: signing_key.into(),
+            verification_keys: Vec::new(),
+        }
+    }
+
+    /// Adds an older key which is only used to verify cookies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, Keyring};
+    ///
+    /// let old_keyring = Keyring::new(b"the key which was used last month".to_vec());
+    /// let signed = old_keyring.sign(&Cookie::new("session", "abc"));
+    ///
+    /// let keyring = Keyring::new(b"the key which is used from now on".to_vec())
+    ///     .with_verification_key(b"the key which was used last month".to_vec());
+    ///
+    /// assert_eq!("abc", keyring.verify(&signed.as_cookie()).unwrap().get_value());
+    /// ```
+    pub fn with_verification_key<K: Into<Vec<u8>>>(mut self, key: K) -> Keyring {
+        self.verification_keys.push(key.into());
+        self
+    }
+
+    /// Signs the value of the cookie with the signing key.
+    ///
+    /// The name and the value are not validated until the cookie is serialized.
+    pub fn sign(&self, cookie: &Cookie) -> OwnedCookie {
+        let signature = URL_SAFE_NO_PAD.encode(
+            mac(&self.signing_key, cookie.get_name(), cookie.get_value())
+                .finalize()
+                .into_bytes(),
+        );
+
+        let mut value = String::with_capacity(SIGNATURE_LEN + cookie.get_value().len());
+        value.push_str(&signature);
+        value.push_str(cookie.get_value());
+
+        OwnedCookie::from(&Cookie::with_quoted(
+            cookie.get_name(),
+            &value,
+            cookie.is_quoted(),
+        ))
+    }
+
+    /// Verifies the signature of the cookie with each key, and returns the cookie without it.
+    ///
+    /// The signatures are compared in constant time. Fails with [`Error::SignatureError`]
+    /// if the value is not signed, or none of the keys match.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, Error, Keyring, SignatureErrorKind};
+    ///
+    /// let keyring = Keyring::new(b"an example key which is long enough".to_vec());
+    /// let signed = keyring.sign(&Cookie::new("role", "user")).to_string();
+    ///
+    /// let tampered = signed.replace("user", "admin");
+    /// match keyring.verify(&Cookie::parse(&tampered).unwrap()[0]) {
+    ///     Err(Error::SignatureError(err)) => assert_eq!(SignatureErrorKind::Mismatch, err.kind()),
+    ///     _ => panic!("the cookie should have been rejected"),
+    /// }
+    /// ```
+    pub fn verify<'a>(&self, cookie: &Cookie<'a>) -> Result<Cookie<'a>, Error> {
+        let signed_value = cookie.get_value();
+        let (signature, value) = match (
+            signed_value.get(..SIGNATURE_LEN),
+            signed_value.get(SIGNATURE_LEN..),
+        ) {
+            (Some(signature), Some(value)) => (signature, value),
+            _ => return Err(SignatureError::new(SignatureErrorKind::Malformed).into_error()),
+        };
+
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| SignatureError::new(SignatureErrorKind::Malformed).into_error())?;
+
+        let is_valid = std::iter::once(&self.signing_key)
+            .chain(self.verification_keys.iter())
+            .any(|key| {
+                mac(key, cookie.get_name(), value)
+                    .verify_slice(&signature)
+                    .is_ok()
+            });
+
+        if !is_valid {
+            return Err(SignatureError::new(SignatureErrorKind::Mismatch).into_error());
+        }
+
+        let verified = Cookie::with_quoted(cookie.get_name(), value, cookie.is_quoted());
+        Ok(match (cookie.name_span(), cookie.value_span()) {
+            (Some(name_span), Some(value_span)) => {
+                verified.with_spans(name_span, value_span.start + SIGNATURE_LEN..value_span.end)
+            }
+            _ => verified,
+        })
+    }
+
+    /// Encrypts the value of the cookie with AES-256-GCM, using a key derived from the signing key
+    /// and a random nonce.
+    ///
+    /// The name is authenticated as associated data, so the encrypted value is rejected
+    /// if it is moved to a cookie with a different name. The nonce, the ciphertext and the tag
+    /// are encoded with the URL-safe base64 alphabet, which only uses characters allowed in cookie values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, Keyring};
+    ///
+    /// let keyring = Keyring::new(b"an example key which is long enough".to_vec());
+    ///
+    /// let encrypted = keyring.encrypt(&Cookie::new("prefs", "theme=dark"));
+    /// assert!(!encrypted.get_value().contains("dark"));
+    ///
+    /// let decrypted = keyring.decrypt(&encrypted.as_cookie()).unwrap();
+    /// assert_eq!("theme=dark", decrypted.get_value());
+    /// ```
+    #[cfg(feature = "private")]
+    pub fn encrypt(&self, cookie: &Cookie) -> OwnedCookie {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher(&self.signing_key)
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: cookie.get_value().as_bytes(),
+                    aad: cookie.get_name().as_bytes(),
+                },
+            )
+            .expect("AES-GCM encrypts values shorter than 64 GiB");
+
+        let mut data = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+
+        OwnedCookie::new(cookie.get_name(), URL_SAFE_NO_PAD.encode(data))
+    }
+
+    /// Decrypts the value of a cookie encrypted with [`encrypt`](Keyring::encrypt), trying each key.
+    ///
+    /// Fails with [`Error::SignatureError`] if the value is not encrypted, or none of the keys
+    /// can decrypt it, which is also the case if the name or the value was changed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, Error, Keyring, SignatureErrorKind};
+    ///
+    /// let keyring = Keyring::new(b"an example key which is long enough".to_vec());
+    /// let encrypted = keyring.encrypt(&Cookie::new("prefs", "theme=dark"));
+    ///
+    /// match keyring.decrypt(&Cookie::new("session", encrypted.get_value())) {
+    ///     Err(Error::SignatureError(err)) => assert_eq!(SignatureErrorKind::Mismatch, err.kind()),
+    ///     _ => panic!("the cookie should have been rejected"),
+    /// }
+    /// ```
+    #[cfg(feature = "private")]
+    pub fn decrypt(&self, cookie: &Cookie) -> Result<OwnedCookie, Error> {
+        let data = URL_SAFE_NO_PAD
+            .decode(cookie.get_value())
+            .map_err(|_| SignatureError::new(SignatureErrorKind::Malformed).into_error())?;
+        if data.len() < NONCE_LEN {
+            return Err(SignatureError::new(SignatureErrorKind::Malformed).into_error());
+        }
+
+        let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+        let value = std::iter::once(&self.signing_key)
+            .chain(self.verification_keys.iter())
+            .find_map(|key| {
+                cipher(key)
+                    .decrypt(
+                        Nonce::from_slice(nonce),
+                        Payload {
+                            msg: ciphertext,
+                            aad: cookie.get_name().as_bytes(),
+                        },
+                    )
+                    .ok()
+            })
+            .ok_or_else(|| SignatureError::new(SignatureErrorKind::Mismatch).into_error())?;
+
+        let value = String::from_utf8(value)
+            .map_err(|_| SignatureError::new(SignatureErrorKind::Malformed).into_error())?;
+        Ok(OwnedCookie::new(cookie.get_name(), value))
+    }
+}
+
+/// Does not show the keys.
+impl fmt::Debug for Keyring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Keyring")
+            .field("verification_keys", &self.verification_keys.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Creates a MAC over `name=value`. As names cannot contain `=`, different pairs
+/// cannot have the same input.
+fn mac(key: &[u8], name: &str, value: &str) -> HmacSha256 {
+    let mut mac = new_mac(key);
+    mac.update(name.as_bytes());
+    mac.update(b"=");
+    mac.update(value.as_bytes());
+    mac
+}
+
+/// Derives an AES-256 key from a keyring key, so that the same key is not used
+/// for both signing and encryption.
+#[cfg(feature = "private")]
+fn cipher(key: &[u8]) -> Aes256Gcm {
+    let mut mac = new_mac(key);
+    mac.update(ENCRYPTION_KEY_LABEL);
+    Aes256Gcm::new(&mac.finalize().into_bytes())
+}
+
+fn new_mac(key: &[u8]) -> HmacSha256 {
+    <HmacSha256 as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Keyring, SIGNATURE_LEN};
+    use crate::{Cookie, Error, SignatureErrorKind};
+
+    const KEY: &[u8] = b"0123456789abcdef0123456789abcdef";
+    const OLD_KEY: &[u8] = b"fedcba9876543210fedcba9876543210";
+
+    fn error_kind(result: Result<Cookie, Error>) -> SignatureErrorKind {
+        match result {
+            Err(Error::SignatureError(err)) => err.kind(),
+            other => panic!("result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let keyring = Keyring::new(KEY);
+        let header = format!(
+            "a=1; {}; c=3",
+            keyring.sign(&Cookie::new("session", "abc=def"))
+        );
+
+        let parsed_cookies = Cookie::parse(&header).unwrap();
+        let verified = keyring.verify(&parsed_cookies[1]).unwrap();
+
+        assert_eq!("session", verified.get_name());
+        assert_eq!("abc=def", verified.get_value());
+        assert_eq!("abc=def", &header[verified.value_span().unwrap()]);
+        assert_eq!(
+            SignatureErrorKind::Malformed,
+            error_kind(keyring.verify(&parsed_cookies[0]))
+        );
+    }
+
+    #[test]
+    fn serializable() {
+        let signed = Keyring::new(KEY).sign(&Cookie::new("a", "b"));
+
+        assert_eq!(SIGNATURE_LEN + 1, signed.get_value().len());
+        assert!(Cookie::serialize(&[signed.as_cookie()]).is_ok());
+    }
+
+    #[test]
+    fn empty_value() {
+        let keyring = Keyring::new(KEY);
+        let signed = keyring.sign(&Cookie::new("a", ""));
+
+        assert_eq!("", keyring.verify(&signed.as_cookie()).unwrap().get_value());
+    }
+
+    #[test]
+    fn quoted() {
+        let keyring = Keyring::new(KEY);
+        let parsed_cookies = Cookie::parse("a=\"b\"").unwrap();
+        let signed = keyring.sign(&parsed_cookies[0]);
+
+        let header = signed.to_string();
+        assert!(header.starts_with("a=\""));
+
+        let verified = keyring.verify(&Cookie::parse(&header).unwrap()[0]).unwrap();
+        assert_eq!("a=\"b\"", verified.to_string());
+    }
+
+    #[test]
+    fn tampered_value() {
+        let keyring = Keyring::new(KEY);
+        let signed = keyring.sign(&Cookie::new("a", "1"));
+        let tampered = format!("{}2", &signed.get_value()[..SIGNATURE_LEN]);
+
+        assert_eq!(
+            SignatureErrorKind::Mismatch,
+            error_kind(keyring.verify(&Cookie::new("a", &tampered)))
+        );
+    }
+
+    #[test]
+    fn tampered_signature() {
+        let keyring = Keyring::new(KEY);
+        let signed = keyring.sign(&Cookie::new("a", "1"));
+        let mut tampered = signed.get_value().to_string();
+        tampered.replace_range(..1, if tampered.starts_with('A') { "B" } else { "A" });
+
+        assert_eq!(
+            SignatureErrorKind::Mismatch,
+            error_kind(keyring.verify(&Cookie::new("a", &tampered)))
+        );
+    }
+
+    #[test]
+    fn renamed() {
+        let keyring = Keyring::new(KEY);
+        let signed = keyring.sign(&Cookie::new("role", "admin"));
+
+        assert_eq!(
+            SignatureErrorKind::Mismatch,
+            error_kind(keyring.verify(&Cookie::new("other_role", signed.get_value())))
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        let keyring = Keyring::new(KEY);
+
+        assert_eq!(
+            SignatureErrorKind::Malformed,
+            error_kind(keyring.verify(&Cookie::new("a", "short")))
+        );
+        assert_eq!(
+            SignatureErrorKind::Malformed,
+            error_kind(keyring.verify(&Cookie::new("a", &"!".repeat(SIGNATURE_LEN))))
+        );
+    }
+
+    #[test]
+    fn key_rotation() {
+        let old_keyring = Keyring::new(OLD_KEY);
+        let keyring = Keyring::new(KEY).with_verification_key(OLD_KEY);
+
+        let old_signed = old_keyring.sign(&Cookie::new("a", "1"));
+        let signed = keyring.sign(&Cookie::new("a", "1"));
+
+        assert_eq!(
+            "1",
+            keyring.verify(&old_signed.as_cookie()).unwrap().get_value()
+        );
+        assert_eq!(
+            "1",
+            keyring.verify(&signed.as_cookie()).unwrap().get_value()
+        );
+        assert_eq!(
+            SignatureErrorKind::Mismatch,
+            error_kind(old_keyring.verify(&signed.as_cookie()))
+        );
+        assert_eq!(
+            SignatureErrorKind::Mismatch,
+            error_kind(Keyring::new(KEY).verify(&old_signed.as_cookie()))
+        );
+    }
+
+    #[test]
+    fn debug_hides_keys() {
+        let debug = format!("{:?}", Keyring::new(KEY).with_verification_key(OLD_KEY));
+
+        assert!(!debug.contains("0123"));
+        assert!(!debug.contains("48"));
+    }
+
+    #[cfg(feature = "private")]
+    mod private {
+        use super::super::{Keyring, NONCE_LEN};
+        use crate::{Cookie, CookieLexer, Error, SignatureErrorKind};
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+
+        const KEY: &[u8] = b"0123456789abcdef0123456789abcdef";
+        const OLD_KEY: &[u8] = b"fedcba9876543210fedcba9876543210";
+
+        fn error_kind(result: Result<crate::OwnedCookie, Error>) -> SignatureErrorKind {
+            match result {
+                Err(Error::SignatureError(err)) => err.kind(),
+                other => panic!("result: {:?}", other),
+            }
+        }
+
+        #[test]
+        fn round_trip() {
+            let keyring = Keyring::new(KEY);
+            let encrypted = keyring.encrypt(&Cookie::new("prefs", "{\"theme\": \"dark\"}"));
+            let header = format!("a=1; {}", encrypted);
+
+            let parsed_cookies = Cookie::parse(&header).unwrap();
+            let decrypted = keyring.decrypt(&parsed_cookies[1]).unwrap();
+
+            assert_eq!("prefs", decrypted.get_name());
+            assert_eq!("{\"theme\": \"dark\"}", decrypted.get_value());
+        }
+
+        #[test]
+        fn cookie_octets() {
+            let encrypted = Keyring::new(KEY).encrypt(&Cookie::new("a", "\u{e9}; ,\"\\"));
+
+            assert!(CookieLexer::is_cookie_octets(encrypted.get_value()));
+            assert!(Cookie::serialize(&[encrypted.as_cookie()]).is_ok());
+        }
+
+        #[test]
+        fn random_nonce() {
+            let keyring = Keyring::new(KEY);
+            let cookie = Cookie::new("a", "1");
+
+            assert_ne!(
+                keyring.encrypt(&cookie).get_value(),
+                keyring.encrypt(&cookie).get_value()
+            );
+        }
+
+        #[test]
+        fn name_is_associated_data() {
+            let keyring = Keyring::new(KEY);
+            let encrypted = keyring.encrypt(&Cookie::new("prefs", "1"));
+
+            assert_eq!(
+                SignatureErrorKind::Mismatch,
+                error_kind(keyring.decrypt(&Cookie::new("other", encrypted.get_value())))
+            );
+        }
+
+        #[test]
+        fn tampered() {
+            let keyring = Keyring::new(KEY);
+            let encrypted = keyring.encrypt(&Cookie::new("a", "1"));
+
+            let mut data = URL_SAFE_NO_PAD.decode(encrypted.get_value()).unwrap();
+            data[NONCE_LEN] ^= 1;
+            let tampered = URL_SAFE_NO_PAD.encode(data);
+
+            assert_eq!(
+                SignatureErrorKind::Mismatch,
+                error_kind(keyring.decrypt(&Cookie::new("a", &tampered)))
+            );
+        }
+
+        #[test]
+        fn malformed() {
+            let keyring = Keyring::new(KEY);
+
+            assert_eq!(
+                SignatureErrorKind::Malformed,
+                error_kind(keyring.decrypt(&Cookie::new("a", "not base64!")))
+            );
+            assert_eq!(
+                SignatureErrorKind::Malformed,
+                error_kind(keyring.decrypt(&Cookie::new("a", "AAAA")))
+            );
+        }
+
+        #[test]
+        fn key_rotation() {
+            let encrypted = Keyring::new(OLD_KEY).encrypt(&Cookie::new("a", "1"));
+
+            let keyring = Keyring::new(KEY).with_verification_key(OLD_KEY);
+            assert_eq!(
+                "1",
+                keyring.decrypt(&encrypted.as_cookie()).unwrap().get_value()
+            );
+            assert_eq!(
+                SignatureErrorKind::Mismatch,
+                error_kind(Keyring::new(KEY).decrypt(&encrypted.as_cookie()))
+            );
+        }
+
+        #[test]
+        fn not_signed() {
+            let keyring = Keyring::new(KEY);
+            let encrypted = keyring.encrypt(&Cookie::new("a", "1"));
+
+            assert!(keyring.verify(&encrypted.as_cookie()).is_err());
+        }
+    }
+}
@@ -1,9 +1,52 @@
 #[macro_use]
 pub(crate) extern crate lalrpop_util;
 
+mod byte_cookie;
 mod cookie;
+mod cookie_date;
+mod cookie_iter;
+mod cookie_jar;
 mod cookie_lexer;
+mod cookie_map;
+mod cookie_matching;
+mod cookie_serializer;
+#[cfg(feature = "http")]
+mod header_map;
+#[cfg(feature = "signed")]
+mod keyring;
 mod linked_list;
+mod netscape;
+mod owned_cookie;
+mod parser;
+#[cfg(feature = "public-suffix")]
+mod public_suffix;
+mod request_url;
+#[cfg(feature = "serde")]
+mod serde_time;
+mod set_cookie;
+mod set_cookie_builder;
 
-pub use cookie::{Cookie, Error};
+pub use byte_cookie::ByteCookie;
+pub use cookie::{
+    Cookie, DuplicateError, Error, InternalError, NetscapeError, NetscapeErrorKind, ParseError,
+    ParseErrorKind, PrefixError, PrefixErrorKind, SerializeError, SerializeErrorKind, StorageError,
+    StorageErrorKind,
+};
+#[cfg(feature = "signed")]
+pub use cookie::{SignatureError, SignatureErrorKind};
+pub use cookie_date::parse_cookie_date;
+pub use cookie_iter::CookieIter;
+pub use cookie_jar::{CookieApi, CookieJar, StoredCookie};
 pub(crate) use cookie_lexer::{CookieLexer, CookieLexerError, CookieToken};
+pub use cookie_map::{CookieMap, DuplicatePolicy};
+pub use cookie_matching::{default_path, domain_match, path_match};
+#[cfg(feature = "http")]
+pub use header_map::{append_set_cookie, cookies_from_headers, set_cookies_from_headers};
+#[cfg(feature = "signed")]
+pub use keyring::Keyring;
+pub use owned_cookie::OwnedCookie;
+pub use parser::{ParseDiagnostic, Parser, PartialParse};
+#[cfg(feature = "public-suffix")]
+pub use public_suffix::PublicSuffixList;
+pub use set_cookie::{SameSite, SetCookie, SetCookieAttribute};
+pub use set_cookie_builder::SetCookieBuilder;
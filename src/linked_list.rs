@@ -19,13 +19,13 @@ impl<T> LinkedList<T> {
     }
 
     pub(crate) fn iter<'a>(&'a self) -> LinkedListIterator<'a, T> {
-        LinkedListIterator { tail: Some(&self) }
+        LinkedListIterator { tail: Some(self) }
     }
 }
 
 impl<T: Clone> LinkedList<T> {
     pub(crate) fn clone_to_vec(&self) -> Vec<T> {
-        self.iter().map(|item| item.clone()).collect::<Vec<T>>()
+        self.iter().cloned().collect::<Vec<T>>()
     }
 }
 
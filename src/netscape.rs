@@ -0,0 +1,338 @@
+use super::cookie::{NetscapeError, NetscapeErrorKind};
+use super::cookie_date::{system_time_from_unix_seconds, unix_seconds_from_system_time};
+use super::{Error, StoredCookie};
+use std::time::SystemTime;
+
+/// The first line of the files written by browsers and curl.
+pub(crate) const HEADER: &str = "# Netscape HTTP Cookie File";
+
+/// Marks `HttpOnly` cookies, which older readers skip as comments.
+const HTTP_ONLY_PREFIX: &str = "#HttpOnly_";
+
+/// A cookie read from a line of a Netscape `cookies.txt` file.
+pub(crate) struct NetscapeLine<'a> {
+    pub(crate) line: usize,
+    pub(crate) domain: &'a str,
+    pub(crate) include_subdomains: bool,
+    pub(crate) path: &'a str,
+    pub(crate) secure: bool,
+    pub(crate) expiry_time: Option<SystemTime>,
+    pub(crate) name: &'a str,
+    pub(crate) value: &'a str,
+    pub(crate) http_only: bool,
+}
+
+/// Reads every cookie from the input, skipping empty lines and comments.
+pub(crate) fn parse(input: &str) -> Result<Vec<NetscapeLine<'_>>, Error> {
+    let mut cookies = Vec::new();
+
+    for (idx, line) in input.lines().enumerate() {
+        let (line, http_only) = match line.strip_prefix(HTTP_ONLY_PREFIX) {
+            Some(line) => (line, true),
+            None if line.starts_with('#') || line.trim().is_empty() => continue,
+            None => (line, false),
+        };
+
+        cookies.push(parse_line(idx + 1, line, http_only)?);
+    }
+
+    Ok(cookies)
+}
+
+fn parse_line(line: usize, input: &str, http_only: bool) -> Result<NetscapeLine<'_>, Error> {
+    let error = |kind| NetscapeError::new(line, kind).into_error();
+
+    let fields = input.splitn(7, '\t').collect::<Vec<&str>>();
+    let [domain, include_subdomains, path, secure, expiry, name, value] = fields[..] else {
+        return Err(error(NetscapeErrorKind::FieldCount));
+    };
+
+    let domain = domain.strip_prefix('.').unwrap_or(domain);
+    if domain.is_empty() {
+        return Err(error(NetscapeErrorKind::EmptyDomain));
+    }
+
+    if !path.starts_with('/') {
+        return Err(error(NetscapeErrorKind::InvalidPath));
+    }
+
+    let expiry_time = match expiry.parse::<i64>() {
+        Ok(0) => None,
+        Ok(seconds) if seconds > 0 => Some(system_time_from_unix_seconds(seconds)),
+        _ => return Err(error(NetscapeErrorKind::InvalidExpiry)),
+    };
+
+    Ok(NetscapeLine {
+        line,
+        domain,
+        include_subdomains: parse_flag(include_subdomains)
+            .ok_or_else(|| error(NetscapeErrorKind::InvalidFlag))?,
+        path,
+        secure: parse_flag(secure).ok_or_else(|| error(NetscapeErrorKind::InvalidFlag))?,
+        expiry_time,
+        name,
+        value,
+        http_only,
+    })
+}
+
+fn parse_flag(input: &str) -> Option<bool> {
+    if input.eq_ignore_ascii_case("TRUE") {
+        Some(true)
+    } else if input.eq_ignore_ascii_case("FALSE") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// Appends a line for the cookie to the output. Session cookies are written with an expiry of 0.
+pub(crate) fn write_line(output: &mut String, cookie: &StoredCookie) {
+    if cookie.is_http_only() {
+        output.push_str(HTTP_ONLY_PREFIX);
+    }
+    if !cookie.is_host_only() {
+        output.push('.');
+    }
+
+    let expiry = match cookie.get_expiry_time() {
+        Some(expiry_time) => unix_seconds_from_system_time(expiry_time).max(1),
+        None => 0,
+    };
+
+    output.push_str(&format!(
+        "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
+        cookie.get_domain(),
+        write_flag(!cookie.is_host_only()),
+        cookie.get_path(),
+        write_flag(cookie.is_secure_only()),
+        expiry,
+        cookie.get_name(),
+        cookie.get_value()
+    ));
+}
+
+fn write_flag(flag: bool) -> &'static str {
+    if flag {
+        "TRUE"
+    } else {
+        "FALSE"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cookie_date::system_time_from_unix_seconds;
+    use crate::{CookieApi, CookieJar, Error, NetscapeErrorKind};
+    use std::time::SystemTime;
+
+    const COOKIES_TXT: &str = "\
+# Netscape HTTP Cookie File
+# https://curl.se/docs/http-cookies.html
+
+.example.com\tTRUE\t/\tFALSE\t0\tsession\tabc
+example.com\tFALSE\t/docs\tTRUE\t1700000000\tlang\ten
+#HttpOnly_.example.com\tTRUE\t/\tTRUE\t1700000000\tid\ta3fWa
+other.org\tfalse\t/\tfalse\t1500000000\texpired\t1
+";
+
+    fn now() -> SystemTime {
+        system_time_from_unix_seconds(1_600_000_000)
+    }
+
+    fn error_at(input: &str) -> (usize, NetscapeErrorKind) {
+        match CookieJar::new().load_netscape_at(input, now()) {
+            Err(Error::NetscapeError(err)) => (err.line(), err.kind()),
+            other => panic!("input: {:?}, result: {:?}", input, other),
+        }
+    }
+
+    #[test]
+    fn load() {
+        let mut jar = CookieJar::new();
+        jar.load_netscape_at(COOKIES_TXT, now()).unwrap();
+
+        assert_eq!(3, jar.len());
+
+        let cookies = jar.iter().collect::<Vec<_>>();
+        assert_eq!("session", cookies[0].get_name());
+        assert_eq!("example.com", cookies[0].get_domain());
+        assert!(!cookies[0].is_host_only());
+        assert!(!cookies[0].is_persistent());
+        assert_eq!(None, cookies[0].get_expiry_time());
+
+        assert_eq!("lang", cookies[1].get_name());
+        assert_eq!("/docs", cookies[1].get_path());
+        assert!(cookies[1].is_host_only());
+        assert!(cookies[1].is_secure_only());
+        assert!(!cookies[1].is_http_only());
+        assert_eq!(
+            Some(system_time_from_unix_seconds(1_700_000_000)),
+            cookies[1].get_expiry_time()
+        );
+
+        assert_eq!("id", cookies[2].get_name());
+        assert!(cookies[2].is_http_only());
+    }
+
+    #[test]
+    fn load_retrievable() {
+        let mut jar = CookieJar::new();
+        jar.load_netscape_at(COOKIES_TXT, now()).unwrap();
+
+        assert_eq!(
+            Some("lang=en; session=abc; id=a3fWa".to_string()),
+            jar.cookie_header_at("https://example.com/docs/", now())
+                .unwrap()
+        );
+        assert_eq!(
+            vec!["session"],
+            jar.cookies_at("http://www.example.com/", now(), CookieApi::NonHttp)
+                .unwrap()
+                .iter()
+                .map(|cookie| cookie.get_name())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut jar = CookieJar::new();
+        jar.load_netscape_at(COOKIES_TXT, now()).unwrap();
+
+        let output = jar.to_netscape();
+        assert_eq!(
+            "\
+# Netscape HTTP Cookie File
+.example.com\tTRUE\t/\tFALSE\t0\tsession\tabc
+example.com\tFALSE\t/docs\tTRUE\t1700000000\tlang\ten
+#HttpOnly_.example.com\tTRUE\t/\tTRUE\t1700000000\tid\ta3fWa
+",
+            output
+        );
+
+        let mut loaded = CookieJar::new();
+        loaded.load_netscape_at(&output, now()).unwrap();
+        assert_eq!(output, loaded.to_netscape());
+    }
+
+    #[test]
+    fn stored_cookies() {
+        let mut jar = CookieJar::new();
+        jar.store_at(
+            "id=\"a3fWa\"; Domain=example.com; Max-Age=60",
+            "https://www.example.com/",
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            "# Netscape HTTP Cookie File\n.example.com\tTRUE\t/\tFALSE\t1600000060\tid\t\"a3fWa\"\n",
+            jar.to_netscape()
+        );
+    }
+
+    #[test]
+    fn crlf_and_empty_value() {
+        let mut jar = CookieJar::new();
+        jar.load_netscape_at("example.com\tFALSE\t/\tFALSE\t0\tempty\t\r\n", now())
+            .unwrap();
+
+        assert_eq!("", jar.iter().next().unwrap().get_value());
+    }
+
+    #[test]
+    fn domain_case() {
+        let mut jar = CookieJar::new();
+        jar.load_netscape_at(
+            "Example.COM\tFALSE\t/\tFALSE\t0\ta\t1\n.Example.ORG\tTRUE\t/\tFALSE\t0\tb\t2\n",
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            vec!["example.com", "example.org"],
+            jar.iter()
+                .map(|cookie| cookie.get_domain())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            Some("a=1".to_string()),
+            jar.cookie_header_at("https://example.com/", now()).unwrap()
+        );
+        assert_eq!(
+            Some("b=2".to_string()),
+            jar.cookie_header_at("https://www.example.org/", now())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn replaces_existing_cookie() {
+        let mut jar = CookieJar::new();
+        jar.load_netscape_at(
+            "example.com\tFALSE\t/\tFALSE\t0\ta\t1\nexample.com\tFALSE\t/\tFALSE\t0\ta\t2\n",
+            now(),
+        )
+        .unwrap();
+
+        assert_eq!(1, jar.len());
+        assert_eq!("2", jar.iter().next().unwrap().get_value());
+    }
+
+    #[test]
+    fn line_numbered_errors() {
+        assert_eq!(
+            (3, NetscapeErrorKind::FieldCount),
+            error_at("# comment\n\nexample.com\tFALSE\t/\tFALSE\t0\tname\n")
+        );
+        assert_eq!(
+            (1, NetscapeErrorKind::EmptyDomain),
+            error_at(".\tFALSE\t/\tFALSE\t0\ta\t1")
+        );
+        assert_eq!(
+            (1, NetscapeErrorKind::InvalidPath),
+            error_at("example.com\tFALSE\tdocs\tFALSE\t0\ta\t1")
+        );
+        assert_eq!(
+            (1, NetscapeErrorKind::InvalidPath),
+            error_at("example.com\tFALSE\t\tFALSE\t0\ta\t1")
+        );
+        assert_eq!(
+            (1, NetscapeErrorKind::InvalidFlag),
+            error_at("example.com\tyes\t/\tFALSE\t0\ta\t1")
+        );
+        assert_eq!(
+            (2, NetscapeErrorKind::InvalidFlag),
+            error_at("example.com\tFALSE\t/\tFALSE\t0\ta\t1\nexample.com\tFALSE\t/\t1\t0\ta\t1")
+        );
+        assert_eq!(
+            (1, NetscapeErrorKind::InvalidExpiry),
+            error_at("example.com\tFALSE\t/\tFALSE\t-1\ta\t1")
+        );
+        assert_eq!(
+            (1, NetscapeErrorKind::InvalidExpiry),
+            error_at("example.com\tFALSE\t/\tFALSE\tnever\ta\t1")
+        );
+        assert_eq!(
+            (1, NetscapeErrorKind::InvalidNameOrValue),
+            error_at("example.com\tFALSE\t/\tFALSE\t0\ta b\t1")
+        );
+        assert_eq!(
+            (1, NetscapeErrorKind::InvalidNameOrValue),
+            error_at("example.com\tFALSE\t/\tFALSE\t0\ta\t1;2")
+        );
+    }
+
+    #[test]
+    fn error_loads_nothing() {
+        let mut jar = CookieJar::new();
+        let result = jar.load_netscape_at(
+            "example.com\tFALSE\t/\tFALSE\t0\ta\t1\nexample.com\tFALSE\t/\tFALSE\t0\tb\n",
+            now(),
+        );
+
+        assert!(result.is_err());
+        assert!(jar.is_empty());
+    }
+}
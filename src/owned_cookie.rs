@@ -0,0 +1,232 @@
+use super::Cookie;
+use std::fmt::{Display, Error as FormatterError, Formatter};
+
+/// A cookie which owns its name and value, so that it can outlive the string it was parsed from.
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::{Cookie, OwnedCookie};
+///
+/// struct Session {
+///     cookies: Vec<OwnedCookie>,
+/// }
+///
+/// let header = String::from("session=abc; theme=dark");
+/// let session = Session {
+///     cookies: Cookie::parse(&header)
+///         .unwrap()
+///         .into_iter()
+///         .map(Cookie::into_owned)
+///         .collect(),
+/// };
+/// drop(header);
+///
+/// assert_eq!("session", session.cookies[0].get_name());
+/// assert_eq!("dark", session.cookies[1].get_value());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedCookie {
+    name: String,
+    value: String,
+    #[cfg_attr(feature = "serde", serde(default))]
+    quoted: bool,
+}
+
+impl OwnedCookie {
+    /// Creates a cookie with the given name and value.
+    ///
+    /// The name and the value are not validated until the cookie is serialized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::OwnedCookie;
+    ///
+    /// let cookie = OwnedCookie::new("name", String::from("value"));
+    /// assert_eq!("name", cookie.get_name());
+    /// assert_eq!("value", cookie.get_value());
+    /// ```
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, value: V) -> OwnedCookie {
+        OwnedCookie {
+            name: name.into(),
+            value: value.into(),
+            quoted: false,
+        }
+    }
+
+    /// Gets the name of the cookie.
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    /// Gets the value of the cookie.
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
+    /// Whether the value was enclosed in double quotes, which are not part of the value,
+    /// in the cookie it was copied from.
+    pub fn is_quoted(&self) -> bool {
+        self.quoted
+    }
+
+    /// Borrows the name and the value as a [`Cookie`], e.g. to serialize it with [`Cookie::serialize`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{Cookie, OwnedCookie};
+    ///
+    /// let cookies = vec![OwnedCookie::new("a", "1"), OwnedCookie::new("b", "2")];
+    /// let header = Cookie::serialize(
+    ///     &cookies.iter().map(OwnedCookie::as_cookie).collect::<Vec<Cookie>>()
+    /// ).unwrap();
+    ///
+    /// assert_eq!("a=1; b=2", header);
+    /// ```
+    pub fn as_cookie(&self) -> Cookie<'_> {
+        Cookie::with_quoted(&self.name, &self.value, self.quoted)
+    }
+}
+
+impl From<&Cookie<'_>> for OwnedCookie {
+    fn from(cookie: &Cookie<'_>) -> OwnedCookie {
+        OwnedCookie {
+            name: cookie.get_name().to_string(),
+            value: cookie.get_value().to_string(),
+            quoted: cookie.is_quoted(),
+        }
+    }
+}
+
+impl From<Cookie<'_>> for OwnedCookie {
+    fn from(cookie: Cookie<'_>) -> OwnedCookie {
+        OwnedCookie::from(&cookie)
+    }
+}
+
+impl PartialEq<Cookie<'_>> for OwnedCookie {
+    fn eq(&self, other: &Cookie<'_>) -> bool {
+        self.as_cookie() == *other
+    }
+}
+
+impl PartialEq<OwnedCookie> for Cookie<'_> {
+    fn eq(&self, other: &OwnedCookie) -> bool {
+        *self == other.as_cookie()
+    }
+}
+
+/// Formats the cookie the same way as [`Cookie`] does.
+impl Display for OwnedCookie {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FormatterError> {
+        self.as_cookie().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OwnedCookie;
+    use crate::Cookie;
+    use std::collections::HashSet;
+
+    #[test]
+    fn from_cookie() {
+        const COOKIE_STR: &str = "a=1; b=\"2\"";
+        let parsed_cookies = Cookie::parse(COOKIE_STR).unwrap();
+
+        let owned_cookies = parsed_cookies
+            .iter()
+            .map(OwnedCookie::from)
+            .collect::<Vec<OwnedCookie>>();
+
+        assert_eq!("a", owned_cookies[0].get_name());
+        assert_eq!("1", owned_cookies[0].get_value());
+        assert_eq!(parsed_cookies[0], owned_cookies[0]);
+        assert_eq!(owned_cookies[1], parsed_cookies[1]);
+    }
+
+    #[test]
+    fn to_owned_cookie() {
+        const COOKIE_STR: &str = "a=1; b=\"2\"";
+        let parsed_cookies = Cookie::parse(COOKIE_STR).unwrap();
+
+        let owned_cookies = parsed_cookies
+            .iter()
+            .map(Cookie::to_owned_cookie)
+            .collect::<Vec<OwnedCookie>>();
+
+        assert_eq!(parsed_cookies, owned_cookies);
+        assert!(!owned_cookies[0].is_quoted());
+        assert!(owned_cookies[1].is_quoted());
+        assert_eq!("2", owned_cookies[1].get_value());
+        assert!(!OwnedCookie::new("a", "\"1\"").is_quoted());
+    }
+
+    #[test]
+    fn round_trip() {
+        const COOKIE_STR: &str = "a=1; b=\"2\"";
+        let owned_cookies = Cookie::parse(COOKIE_STR)
+            .unwrap()
+            .into_iter()
+            .map(Cookie::into_owned)
+            .collect::<Vec<OwnedCookie>>();
+
+        let cookies = owned_cookies
+            .iter()
+            .map(OwnedCookie::as_cookie)
+            .collect::<Vec<Cookie>>();
+        assert_eq!(COOKIE_STR, Cookie::serialize(&cookies).unwrap());
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!("a=1", OwnedCookie::new("a", "1").to_string());
+        assert_eq!(
+            "b=\"2\"",
+            Cookie::parse("b=\"2\"").unwrap()[0]
+                .clone()
+                .into_owned()
+                .to_string()
+        );
+    }
+
+    #[test]
+    fn hash() {
+        let cookies = [
+            OwnedCookie::new("a", "1"),
+            OwnedCookie::new("a", "1"),
+            OwnedCookie::new("a", "2"),
+        ]
+        .into_iter()
+        .collect::<HashSet<OwnedCookie>>();
+
+        assert_eq!(2, cookies.len());
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::OwnedCookie;
+        use crate::Cookie;
+
+        #[test]
+        fn round_trip() {
+            let cookie = OwnedCookie::from(&Cookie::parse("id=\"a3fWa\"").unwrap()[0]);
+            let json = serde_json::to_string(&cookie).unwrap();
+
+            assert_eq!(r#"{"name":"id","value":"a3fWa","quoted":true}"#, json);
+            assert_eq!(cookie, serde_json::from_str::<OwnedCookie>(&json).unwrap());
+        }
+
+        #[test]
+        fn deserialize_escaped() {
+            let cookie: OwnedCookie =
+                serde_json::from_str(r#"{"name":"a\u0062","value":"1"}"#).unwrap();
+
+            assert_eq!("ab", cookie.get_name());
+            assert_eq!(OwnedCookie::new("ab", "1"), cookie);
+        }
+    }
+}
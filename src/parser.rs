@@ -0,0 +1,446 @@
+use super::cookie::ParseError;
+use super::cookie_iter::RecoveringCookieIter;
+use super::{Cookie, Error, ParseErrorKind};
+use std::ops::Range;
+
+/// Parses `Cookie` headers with configurable strictness.
+///
+/// By default, the input must match the grammar of
+/// [RFC 6265, Section 4.2.1](https://tools.ietf.org/html/rfc6265.html#section-4.2.1),
+/// the same way as with [`Cookie::parse`].
+///
+/// # Examples
+///
+/// ```
+/// use basic_cookies::Parser;
+///
+/// const INPUT: &str = "session=abc;; prefs={\"theme\": \"dark\"};\tlang=en;";
+///
+/// assert!(Parser::new().parse(INPUT).is_err());
+///
+/// let cookies = Parser::new().lenient(true).parse(INPUT).unwrap();
+/// assert_eq!(3, cookies.len());
+/// assert_eq!("{\"theme\": \"dark\"}", cookies[1].get_value());
+/// assert_eq!("lang", cookies[2].get_name());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Parser {
+    lenient: bool,
+}
+
+impl Parser {
+    /// Creates a strict parser.
+    pub fn new() -> Parser {
+        Parser::default()
+    }
+
+    /// Sets whether the input is parsed the way browsers and most servers do, as described in
+    /// [RFC 6265, Section 5.2](https://tools.ietf.org/html/rfc6265.html#section-5.2).
+    ///
+    /// The input is split on `;`, and each part is split on its first `=` into a name and a value,
+    /// with leading and trailing whitespace removed. A part without `=` is a value with an empty name.
+    /// Parts which are empty, or contain control characters other than tabs, are skipped.
+    /// Values which are enclosed in double quotes are returned without the quotes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::Parser;
+    ///
+    /// let cookies = Parser::new().lenient(true).parse("a=b c; d=e,f").unwrap();
+    ///
+    /// assert_eq!("b c", cookies[0].get_value());
+    /// assert_eq!("e,f", cookies[1].get_value());
+    /// ```
+    pub fn lenient(mut self, lenient: bool) -> Parser {
+        self.lenient = lenient;
+        self
+    }
+
+    /// Parses the value of a `Cookie` header.
+    ///
+    /// In lenient mode, no error is returned, as malformed cookies are skipped.
+    pub fn parse<'a>(&self, input: &'a str) -> Result<Vec<Cookie<'a>>, Error> {
+        if self.lenient {
+            Ok(self.parse_partial(input).into_cookies())
+        } else {
+            Cookie::parse(input)
+        }
+    }
+
+    /// Parses the value of a `Cookie` header, skipping the cookies which cannot be parsed
+    /// instead of failing, and returning a diagnostic for each of them.
+    ///
+    /// In strict mode, the input following an error is skipped up to the next `;`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{ParseErrorKind, Parser};
+    ///
+    /// let result = Parser::new().parse_partial("session=abc; prefs=a b; lang=en");
+    ///
+    /// assert_eq!(2, result.cookies().len());
+    /// assert_eq!("lang", result.cookies()[1].get_name());
+    ///
+    /// let diagnostic = &result.diagnostics()[0];
+    /// assert_eq!(13..22, diagnostic.span());
+    /// assert_eq!(ParseErrorKind::UnexpectedCharacter('b'), diagnostic.error().kind());
+    /// ```
+    pub fn parse_partial<'a>(&self, input: &'a str) -> PartialParse<'a> {
+        let mut result = PartialParse {
+            cookies: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+
+        let mut push = |item| match item {
+            Ok(cookie) => result.cookies.push(cookie),
+            Err(diagnostic) => result.diagnostics.push(diagnostic),
+        };
+
+        if self.lenient {
+            lenient_pairs(input)
+                .filter_map(|span| parse_lenient_pair(input, span))
+                .for_each(&mut push);
+        } else {
+            RecoveringCookieIter::new(input).for_each(&mut push);
+        }
+
+        result
+    }
+}
+
+/// The result of [`Parser::parse_partial`].
+#[derive(Debug)]
+pub struct PartialParse<'a> {
+    cookies: Vec<Cookie<'a>>,
+    diagnostics: Vec<ParseDiagnostic>,
+}
+
+impl<'a> PartialParse<'a> {
+    /// Gets the cookies which could be parsed.
+    pub fn cookies(&self) -> &[Cookie<'a>] {
+        &self.cookies
+    }
+
+    /// Gets a diagnostic for each cookie which was skipped, in the order of the input.
+    pub fn diagnostics(&self) -> &[ParseDiagnostic] {
+        &self.diagnostics
+    }
+
+    /// Whether every cookie could be parsed.
+    pub fn is_complete(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    /// Takes the cookies which could be parsed, discarding the diagnostics.
+    pub fn into_cookies(self) -> Vec<Cookie<'a>> {
+        self.cookies
+    }
+}
+
+/// Describes a cookie which was skipped by [`Parser::parse_partial`].
+#[derive(Debug)]
+pub struct ParseDiagnostic {
+    span: Range<usize>,
+    error: ParseError,
+}
+
+impl ParseDiagnostic {
+    pub(crate) fn new(span: Range<usize>, error: ParseError) -> ParseDiagnostic {
+        ParseDiagnostic { span, error }
+    }
+
+    /// Gets the byte range of the input which was skipped.
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Gets the error which caused the cookie to be skipped.
+    pub fn error(&self) -> &ParseError {
+        &self.error
+    }
+}
+
+/// Splits the input on `;`, and gets the span of each part without leading and trailing whitespace.
+fn lenient_pairs(input: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut start = 0;
+    input.split(';').map(move |part| {
+        let part_start = start;
+        start += part.len() + 1;
+
+        let leading = part.len() - part.trim_start_matches([' ', '\t']).len();
+        let trimmed_start = part_start + leading;
+        trimmed_start..trimmed_start + trim_whitespace(part).len()
+    })
+}
+
+fn parse_lenient_pair(
+    input: &str,
+    span: Range<usize>,
+) -> Option<Result<Cookie<'_>, ParseDiagnostic>> {
+    let pair = &input[span.clone()];
+    if pair.is_empty() {
+        return None;
+    }
+
+    if let Some(offset) = pair.find(|c: char| c != '\t' && c.is_ascii_control()) {
+        let error = ParseError::new(input.as_bytes(), span.start + offset, Vec::new());
+        return Some(Err(ParseDiagnostic::new(span, error)));
+    }
+
+    let (name, value) = match pair.split_once('=') {
+        Some((name, value)) => (trim_whitespace(name), trim_whitespace(value)),
+        None => (&pair[..0], pair),
+    };
+
+    if name.is_empty() && value.is_empty() {
+        let error = ParseError::with_kind(
+            ParseErrorKind::EmptyCookiePair,
+            span.start,
+            vec!["cookie-octet"],
+        );
+        return Some(Err(ParseDiagnostic::new(span, error)));
+    }
+
+    let cookie = match value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(unquoted) if !unquoted.is_empty() => Cookie::with_quoted(name, unquoted, true),
+        _ => Cookie::with_quoted(name, value, false),
+    };
+
+    let name_span = span_in(input, cookie.get_name());
+    let value_span = span_in(input, cookie.get_value());
+    Some(Ok(cookie.with_spans(name_span, value_span)))
+}
+
+/// Gets the byte range of `part`, which must be a slice of `input`.
+fn span_in(input: &str, part: &str) -> Range<usize> {
+    let start = part.as_ptr() as usize - input.as_ptr() as usize;
+    start..start + part.len()
+}
+
+fn trim_whitespace(value: &str) -> &str {
+    value.trim_matches([' ', '\t'])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Parser;
+    use crate::ParseErrorKind;
+
+    fn lenient_pairs(input: &str) -> Vec<(&str, &str)> {
+        Parser::new()
+            .lenient(true)
+            .parse(input)
+            .unwrap()
+            .iter()
+            .map(|cookie| (cookie.get_name(), cookie.get_value()))
+            .collect()
+    }
+
+    #[test]
+    fn strict_by_default() {
+        const COOKIE_STR: &str = "a=1; b=2 3";
+
+        assert!(Parser::new().parse(COOKIE_STR).is_err());
+        assert!(Parser::new().lenient(false).parse(COOKIE_STR).is_err());
+        assert_eq!(2, Parser::new().parse("a=1; b=2").unwrap().len());
+    }
+
+    #[test]
+    fn lenient_same_as_strict_for_valid_input() {
+        const COOKIE_STR: &str =
+            "  test1=0x1234; test2=\"quoted\"; =nokey; novalue=; test=abc=123 ";
+
+        assert_eq!(
+            crate::Cookie::parse(COOKIE_STR).unwrap(),
+            Parser::new().lenient(true).parse(COOKIE_STR).unwrap()
+        );
+    }
+
+    #[test]
+    fn lenient_spaces_and_commas() {
+        assert_eq!(
+            vec![("a", "b c"), ("d", "e,f"), ("g h", "i")],
+            lenient_pairs("a=b c; d = e,f ;g h=i")
+        );
+    }
+
+    #[test]
+    fn lenient_separators() {
+        assert_eq!(
+            vec![("a", "1"), ("b", "2"), ("c", "3")],
+            lenient_pairs(";a=1;; b=2;\tc=3;")
+        );
+    }
+
+    #[test]
+    fn lenient_json() {
+        assert_eq!(
+            vec![("prefs", "{\"a\":[1,2],\"b\":\"c\"}")],
+            lenient_pairs("prefs={\"a\":[1,2],\"b\":\"c\"}")
+        );
+    }
+
+    #[test]
+    fn lenient_skips_malformed_pairs() {
+        assert_eq!(
+            vec![("a", "1"), ("c", "3")],
+            lenient_pairs("a=1; b=\x01; =; c=3; d=\x7f")
+        );
+    }
+
+    #[test]
+    fn lenient_no_equals() {
+        assert_eq!(vec![("", "nokey"), ("a", "1")], lenient_pairs("nokey; a=1"));
+    }
+
+    #[test]
+    fn lenient_quotes() {
+        let cookies = Parser::new()
+            .lenient(true)
+            .parse("a=\"1\"; b=\"\"; c=\"2")
+            .unwrap();
+
+        assert_eq!("1", cookies[0].get_value());
+        assert_eq!("a=\"1\"", cookies[0].to_string());
+        assert_eq!("\"\"", cookies[1].get_value());
+        assert_eq!("\"2", cookies[2].get_value());
+    }
+
+    #[test]
+    fn partial_strict() {
+        const COOKIE_STR: &str = "a=1; b=2 3; c=\"4; d=5; e=\u{e9}";
+        let result = Parser::new().parse_partial(COOKIE_STR);
+
+        assert!(!result.is_complete());
+        assert_eq!(
+            vec![("a", "1"), ("d", "5")],
+            result
+                .cookies()
+                .iter()
+                .map(|cookie| (cookie.get_name(), cookie.get_value()))
+                .collect::<Vec<_>>()
+        );
+
+        let diagnostics = result.diagnostics();
+        assert_eq!(3, diagnostics.len());
+
+        assert_eq!("b=2 3", &COOKIE_STR[diagnostics[0].span()]);
+        assert_eq!(
+            ParseErrorKind::UnexpectedCharacter('3'),
+            diagnostics[0].error().kind()
+        );
+        assert_eq!(9, diagnostics[0].error().offset());
+
+        assert_eq!("c=\"4", &COOKIE_STR[diagnostics[1].span()]);
+        assert_eq!(
+            ParseErrorKind::UnterminatedQuote,
+            diagnostics[1].error().kind()
+        );
+
+        assert_eq!("e=\u{e9}", &COOKIE_STR[diagnostics[2].span()]);
+        assert_eq!(
+            ParseErrorKind::UnexpectedCharacter('\u{e9}'),
+            diagnostics[2].error().kind()
+        );
+    }
+
+    #[test]
+    fn partial_strict_empty_pairs() {
+        const COOKIE_STR: &str = "a=1;; b=2;";
+        let result = Parser::new().parse_partial(COOKIE_STR);
+
+        assert_eq!(2, result.cookies().len());
+        assert_eq!(
+            vec![4..4, 10..10],
+            result
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| diagnostic.span())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn partial_strict_valid_input() {
+        const COOKIE_STR: &str = "test1=0x1234; test2=\"quoted\"; =nokey";
+        let result = Parser::new().parse_partial(COOKIE_STR);
+
+        assert!(result.is_complete());
+        assert_eq!(
+            crate::Cookie::parse(COOKIE_STR).unwrap(),
+            result.into_cookies()
+        );
+    }
+
+    #[test]
+    fn partial_lenient() {
+        const COOKIE_STR: &str = "a=1 2;; b=\x01; =; c=3";
+        let result = Parser::new().lenient(true).parse_partial(COOKIE_STR);
+
+        assert_eq!(2, result.cookies().len());
+        assert_eq!(
+            vec![("b=\x01", 10), ("=", 13)],
+            result
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| (&COOKIE_STR[diagnostic.span()], diagnostic.error().offset()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn lenient_empty_pair() {
+        const COOKIE_STR: &str = "a=1; =; b=2; \t=  ";
+        let result = Parser::new().lenient(true).parse_partial(COOKIE_STR);
+
+        assert_eq!(2, result.cookies().len());
+        assert_eq!(
+            vec![
+                (5..6, ParseErrorKind::EmptyCookiePair),
+                (14..15, ParseErrorKind::EmptyCookiePair)
+            ],
+            result
+                .diagnostics()
+                .iter()
+                .map(|diagnostic| (diagnostic.span(), diagnostic.error().kind()))
+                .collect::<Vec<_>>()
+        );
+
+        let strict = Parser::new().parse_partial("a=1; ; b=2");
+        assert_eq!(
+            ParseErrorKind::EmptyCookiePair,
+            strict.diagnostics()[0].error().kind()
+        );
+    }
+
+    #[test]
+    fn lenient_spans() {
+        const COOKIE_STR: &str = "a = 1 ;; nokey; b=\"2\"";
+        let cookies = Parser::new().lenient(true).parse(COOKIE_STR).unwrap();
+
+        assert_eq!(
+            vec![
+                (Some(0..1), Some(4..5)),
+                (Some(9..9), Some(9..14)),
+                (Some(16..17), Some(19..20))
+            ],
+            cookies
+                .iter()
+                .map(|cookie| (cookie.name_span(), cookie.value_span()))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn lenient_non_ascii() {
+        assert_eq!(
+            vec![("name", "\u{e9}t\u{e9}")],
+            lenient_pairs("name=\u{e9}t\u{e9}")
+        );
+    }
+}
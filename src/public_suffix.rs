@@ -0,0 +1,239 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::{Arc, OnceLock};
+
+const EMBEDDED_LIST: &str = include_str!("../data/public_suffix_list.dat");
+
+/// A set of rules from the [Public Suffix List](https://publicsuffix.org/),
+/// used to reject cookies set for domains such as `co.uk`, as described in
+/// [RFC 6265, Section 5.3](https://tools.ietf.org/html/rfc6265.html#section-5.3).
+///
+/// Domains are compared label by label, after converting them to lower case.
+/// Internationalized rules are not converted to punycode, so they only match
+/// domains given in their Unicode form.
+#[derive(Clone, Default)]
+pub struct PublicSuffixList {
+    rules: HashSet<String>,
+    wildcard_rules: HashSet<String>,
+    exception_rules: HashSet<String>,
+}
+
+impl PublicSuffixList {
+    /// Parses a list in the format of `public_suffix_list.dat`.
+    ///
+    /// Each line holds a single rule. Empty lines, comments starting with `//`
+    /// and anything following the first whitespace on a line are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::PublicSuffixList;
+    ///
+    /// let list = PublicSuffixList::parse("// comment\ncom\n*.ck\n!www.ck\n");
+    ///
+    /// assert!(list.is_public_suffix("com"));
+    /// assert!(list.is_public_suffix("example.ck"));
+    /// assert!(!list.is_public_suffix("www.ck"));
+    /// assert!(!list.is_public_suffix("example.com"));
+    /// ```
+    pub fn parse(input: &str) -> PublicSuffixList {
+        let mut list = PublicSuffixList::default();
+
+        for line in input.lines() {
+            let rule = match line.split_whitespace().next() {
+                Some(rule) if !rule.starts_with("//") => rule.to_lowercase(),
+                _ => continue,
+            };
+
+            if let Some(exception) = rule.strip_prefix('!') {
+                if !exception.is_empty() {
+                    list.exception_rules.insert(exception.to_string());
+                }
+            } else if let Some(wildcard) = rule.strip_prefix("*.") {
+                if !wildcard.is_empty() {
+                    list.wildcard_rules.insert(wildcard.to_string());
+                }
+            } else if rule != "*" {
+                list.rules.insert(rule);
+            }
+        }
+
+        list
+    }
+
+    /// Gets the snapshot of the Public Suffix List which is embedded into the crate.
+    ///
+    /// The list is parsed on the first call, and every call returns the same shared list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::PublicSuffixList;
+    ///
+    /// let list = PublicSuffixList::embedded();
+    ///
+    /// assert!(list.is_public_suffix("co.uk"));
+    /// assert!(!list.is_public_suffix("example.co.uk"));
+    /// ```
+    pub fn embedded() -> Arc<PublicSuffixList> {
+        static EMBEDDED: OnceLock<Arc<PublicSuffixList>> = OnceLock::new();
+
+        EMBEDDED
+            .get_or_init(|| Arc::new(PublicSuffixList::parse(EMBEDDED_LIST)))
+            .clone()
+    }
+
+    /// Whether `domain` is a public suffix, using the algorithm described at
+    /// [publicsuffix.org](https://publicsuffix.org/list/).
+    ///
+    /// Top-level domains which are not on the list are treated as public suffixes,
+    /// as the list prescribes through its implicit `*` rule.
+    pub fn is_public_suffix(&self, domain: &str) -> bool {
+        let domain = domain.to_lowercase();
+        if domain.is_empty() {
+            return false;
+        }
+
+        let labels = domain.split('.').count();
+        self.prevailing_rule_labels(&domain) == labels
+    }
+
+    /// Counts the labels of the rule which applies to `domain`.
+    fn prevailing_rule_labels(&self, domain: &str) -> usize {
+        let suffixes = suffixes(domain);
+
+        if let Some(labels) = suffixes
+            .iter()
+            .find(|(_, suffix)| self.exception_rules.contains(*suffix))
+            .map(|(labels, _)| labels - 1)
+        {
+            return labels;
+        }
+
+        suffixes
+            .iter()
+            .filter(|(_, suffix)| self.rules.contains(*suffix))
+            .map(|(labels, _)| *labels)
+            .chain(
+                suffixes
+                    .iter()
+                    .zip(suffixes.iter().skip(1))
+                    .filter(|(_, (_, parent))| self.wildcard_rules.contains(*parent))
+                    .map(|((labels, _), _)| *labels),
+            )
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+impl fmt::Debug for PublicSuffixList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PublicSuffixList")
+            .field("rules", &self.rules.len())
+            .field("wildcard_rules", &self.wildcard_rules.len())
+            .field("exception_rules", &self.exception_rules.len())
+            .finish()
+    }
+}
+
+/// Gets every suffix of `domain` which starts at a label boundary, together with
+/// its number of labels, starting with the whole domain.
+fn suffixes(domain: &str) -> Vec<(usize, &str)> {
+    let labels = domain.split('.').count();
+    let mut suffixes = vec![(labels, domain)];
+
+    for (idx, _) in domain.match_indices('.') {
+        suffixes.push((labels - suffixes.len(), &domain[idx + 1..]));
+    }
+
+    suffixes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PublicSuffixList;
+    use std::sync::Arc;
+
+    const LIST: &str = "\
+// ===BEGIN ICANN DOMAINS===
+com
+uk
+co.uk
+*.ck
+!www.ck
+jp
+*.kawasaki.jp
+!city.kawasaki.jp
+// ===BEGIN PRIVATE DOMAINS===
+github.io  trailing text is ignored
+";
+
+    #[test]
+    fn simple_rules() {
+        let list = PublicSuffixList::parse(LIST);
+
+        assert!(list.is_public_suffix("com"));
+        assert!(list.is_public_suffix("co.uk"));
+        assert!(list.is_public_suffix("github.io"));
+        assert!(!list.is_public_suffix("example.com"));
+        assert!(!list.is_public_suffix("example.co.uk"));
+        assert!(!list.is_public_suffix("www.example.co.uk"));
+        assert!(!list.is_public_suffix("example.github.io"));
+    }
+
+    #[test]
+    fn wildcard_rules() {
+        let list = PublicSuffixList::parse(LIST);
+
+        assert!(list.is_public_suffix("ck"));
+        assert!(list.is_public_suffix("example.ck"));
+        assert!(!list.is_public_suffix("www.example.ck"));
+        assert!(list.is_public_suffix("example.kawasaki.jp"));
+        assert!(!list.is_public_suffix("kawasaki.jp"));
+    }
+
+    #[test]
+    fn exception_rules() {
+        let list = PublicSuffixList::parse(LIST);
+
+        assert!(!list.is_public_suffix("www.ck"));
+        assert!(!list.is_public_suffix("city.kawasaki.jp"));
+        assert!(!list.is_public_suffix("www.city.kawasaki.jp"));
+    }
+
+    #[test]
+    fn implicit_wildcard_rule() {
+        let list = PublicSuffixList::parse(LIST);
+
+        assert!(list.is_public_suffix("example"));
+        assert!(!list.is_public_suffix("www.example"));
+    }
+
+    #[test]
+    fn case_insensitive() {
+        let list = PublicSuffixList::parse(LIST);
+
+        assert!(list.is_public_suffix("CO.UK"));
+        assert!(list.is_public_suffix("Example.CK"));
+        assert!(!list.is_public_suffix("WWW.CK"));
+    }
+
+    #[test]
+    fn empty_domain() {
+        let list = PublicSuffixList::parse(LIST);
+
+        assert!(!list.is_public_suffix(""));
+    }
+
+    #[test]
+    fn embedded() {
+        let list = PublicSuffixList::embedded();
+
+        assert!(list.is_public_suffix("com"));
+        assert!(list.is_public_suffix("co.uk"));
+        assert!(list.is_public_suffix("github.io"));
+        assert!(!list.is_public_suffix("example.co.uk"));
+        assert!(!list.is_public_suffix("www.ck"));
+        assert!(Arc::ptr_eq(&list, &PublicSuffixList::embedded()));
+    }
+}
@@ -0,0 +1,129 @@
+/// The parts of a request URL which are relevant to cookie handling.
+#[derive(Debug, PartialEq)]
+pub(crate) struct RequestUrl<'a> {
+    scheme: &'a str,
+    host: String,
+    path: &'a str,
+}
+
+impl<'a> RequestUrl<'a> {
+    /// Parses an absolute URL such as `https://user@example.com:8080/docs/index.html?q=1`.
+    ///
+    /// The host is converted to lower case, an empty path is replaced with `/`,
+    /// while the user info, the port, the query and the fragment are discarded.
+    pub(crate) fn parse(input: &'a str) -> Option<RequestUrl<'a>> {
+        let scheme_end = input.find("://")?;
+        let scheme = &input[..scheme_end];
+        if scheme.is_empty()
+            || !scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            || !scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        {
+            return None;
+        }
+
+        let rest = &input[scheme_end + 3..];
+        let authority_end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+        let authority = &rest[..authority_end];
+        let host_and_port = match authority.rfind('@') {
+            Some(idx) => &authority[idx + 1..],
+            None => authority,
+        };
+
+        let host = if host_and_port.starts_with('[') {
+            &host_and_port[..host_and_port.find(']')? + 1]
+        } else {
+            match host_and_port.find(':') {
+                Some(idx) => &host_and_port[..idx],
+                None => host_and_port,
+            }
+        };
+
+        if host.is_empty() {
+            return None;
+        }
+
+        let path_and_query = &rest[authority_end..];
+        let path_end = path_and_query
+            .find(['?', '#'])
+            .unwrap_or(path_and_query.len());
+        let path = match &path_and_query[..path_end] {
+            "" => "/",
+            path => path,
+        };
+
+        Some(RequestUrl {
+            scheme,
+            host: host.to_ascii_lowercase(),
+            path,
+        })
+    }
+
+    /// The canonicalized host name, as described in
+    /// [RFC 6265, Section 5.1.2](https://tools.ietf.org/html/rfc6265.html#section-5.1.2).
+    pub(crate) fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// The path, which always starts with `/`.
+    pub(crate) fn path(&self) -> &'a str {
+        self.path
+    }
+
+    /// Whether the scheme denotes a secure protocol.
+    pub(crate) fn is_secure(&self) -> bool {
+        self.scheme.eq_ignore_ascii_case("https") || self.scheme.eq_ignore_ascii_case("wss")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RequestUrl;
+
+    #[test]
+    fn simple() {
+        let url = RequestUrl::parse("http://example.com/docs/index.html").unwrap();
+
+        assert_eq!("example.com", url.host());
+        assert_eq!("/docs/index.html", url.path());
+        assert!(!url.is_secure());
+    }
+
+    #[test]
+    fn all_components() {
+        let url = RequestUrl::parse("HTTPS://user:pw@Example.COM:8443/a/b?c=/d#e").unwrap();
+
+        assert_eq!("example.com", url.host());
+        assert_eq!("/a/b", url.path());
+        assert!(url.is_secure());
+    }
+
+    #[test]
+    fn empty_path() {
+        let url = RequestUrl::parse("http://example.com?q=1").unwrap();
+
+        assert_eq!("example.com", url.host());
+        assert_eq!("/", url.path());
+        assert_eq!(
+            "/",
+            RequestUrl::parse("https://example.com").unwrap().path()
+        );
+    }
+
+    #[test]
+    fn ipv6_host() {
+        let url = RequestUrl::parse("http://[::1]:8080/").unwrap();
+
+        assert_eq!("[::1]", url.host());
+        assert_eq!("/", url.path());
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(None, RequestUrl::parse("example.com/path"));
+        assert_eq!(None, RequestUrl::parse("http:///path"));
+        assert_eq!(None, RequestUrl::parse("1http://example.com/"));
+        assert_eq!(None, RequestUrl::parse("http://[::1/"));
+    }
+}
@@ -0,0 +1,40 @@
+use super::cookie_date::{system_time_from_unix_seconds, unix_seconds_from_system_time};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::time::SystemTime;
+
+/// Represents times as whole seconds since the Unix epoch, as an `i64`, for `#[serde(with = "...")]`.
+/// Times before the epoch are negative, and fractions of a second are rounded towards negative infinity.
+pub(crate) fn serialize<S: Serializer>(
+    time: &SystemTime,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    unix_seconds_from_system_time(*time).serialize(serializer)
+}
+
+pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<SystemTime, D::Error> {
+    i64::deserialize(deserializer).map(system_time_from_unix_seconds)
+}
+
+/// Same as [`serialize`] and [`deserialize`], but for optional times, which are `null` if missing.
+pub(crate) mod option {
+    use super::{system_time_from_unix_seconds, unix_seconds_from_system_time};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::SystemTime;
+
+    pub(crate) fn serialize<S: Serializer>(
+        time: &Option<SystemTime>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        time.map(unix_seconds_from_system_time)
+            .serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<SystemTime>, D::Error> {
+        Option::<i64>::deserialize(deserializer)
+            .map(|seconds| seconds.map(system_time_from_unix_seconds))
+    }
+}
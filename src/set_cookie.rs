@@ -0,0 +1,867 @@
+use super::cookie::{ParseError, PrefixError, PrefixErrorKind};
+use super::{cookie_date, CookieLexer, Error};
+use std::borrow::Cow;
+use std::time::SystemTime;
+
+const SECURE_PREFIX: &str = "__Secure-";
+const HOST_PREFIX: &str = "__Host-";
+
+lalrpop_mod!(
+    #[allow(clippy::all)]
+    set_cookie_grammar
+);
+
+/// A cookie parsed from a `Set-Cookie` header.
+///
+/// With the `serde` feature, deserializing borrows the name, the value and the attributes
+/// from the input, and does not validate them.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SetCookie<'a> {
+    name: &'a str,
+    value: &'a str,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    attributes: Vec<SetCookieAttribute<'a>>,
+}
+
+impl<'a> SetCookie<'a> {
+    /// Parses an [RFC 6265](https://tools.ietf.org/html/rfc6265.html#section-5.2) compliant set-cookie string,
+    /// i.e. the value of a `Set-Cookie` response header.
+    ///
+    /// Attributes which are not recognized, or whose values are not valid,
+    /// are ignored as described in the RFC.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{SetCookie, SetCookieAttribute};
+    ///
+    /// let set_cookie = SetCookie::parse("id=a3fWa; Max-Age=2592000; Secure").unwrap();
+    ///
+    /// assert_eq!("id", set_cookie.get_name());
+    /// assert_eq!("a3fWa", set_cookie.get_value());
+    /// assert_eq!(
+    ///     &[SetCookieAttribute::MaxAge(2592000), SetCookieAttribute::Secure],
+    ///     set_cookie.get_attributes()
+    /// );
+    /// ```
+    ///
+    /// Cookies whose names start with the `__Secure-` or `__Host-` prefix are rejected
+    /// with [`Error::PrefixError`] if they do not meet the requirements of the prefix, as described in
+    /// [RFC 6265bis](https://datatracker.ietf.org/doc/html/draft-ietf-httpbis-rfc6265bis#section-4.1.3).
+    /// The prefixes are matched case-insensitively.
+    ///
+    /// ```
+    /// use basic_cookies::{Error, PrefixErrorKind, SetCookie};
+    ///
+    /// assert!(SetCookie::parse("__Host-id=a3fWa; Secure; Path=/").is_ok());
+    ///
+    /// match SetCookie::parse("__Secure-id=a3fWa") {
+    ///     Err(Error::PrefixError(err)) => assert_eq!(PrefixErrorKind::SecureRequired, err.kind()),
+    ///     _ => panic!("the cookie should have been rejected"),
+    /// }
+    /// ```
+    pub fn parse(input: &'a str) -> Result<SetCookie<'a>, Error> {
+        let set_cookie = set_cookie_grammar::SetCookieParser::new()
+            .parse(CookieLexer::new_set_cookie(input))
+            .map_err(|err| ParseError::from_lalrpop_parse_error_to_error(input, err))?
+            .with_str(input)?;
+
+        set_cookie.check_prefix()?;
+        Ok(set_cookie)
+    }
+
+    /// Gets the name of the cookie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookie;
+    ///
+    /// let set_cookie = SetCookie::parse("name=value; Path=/").unwrap();
+    /// assert_eq!("name", set_cookie.get_name());
+    /// ```
+    pub fn get_name(&self) -> &'a str {
+        self.name
+    }
+
+    /// Gets the value of the cookie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookie;
+    ///
+    /// let set_cookie = SetCookie::parse("name=value; Path=/").unwrap();
+    /// assert_eq!("value", set_cookie.get_value());
+    /// ```
+    pub fn get_value(&self) -> &'a str {
+        self.value
+    }
+
+    /// Gets the recognized attributes of the cookie, in the order they appear in the input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{SetCookie, SetCookieAttribute};
+    ///
+    /// let set_cookie = SetCookie::parse("name=value; Path=/; HttpOnly").unwrap();
+    /// assert_eq!(
+    ///     &[SetCookieAttribute::Path("/"), SetCookieAttribute::HttpOnly],
+    ///     set_cookie.get_attributes()
+    /// );
+    /// ```
+    pub fn get_attributes(&self) -> &[SetCookieAttribute<'a>] {
+        &self.attributes
+    }
+
+    /// Gets the expiry time set by the last valid `Expires` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookie;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let set_cookie = SetCookie::parse("a=b; Expires=Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+    /// assert_eq!(
+    ///     Some(UNIX_EPOCH + Duration::from_secs(784111777)),
+    ///     set_cookie.get_expires()
+    /// );
+    /// ```
+    pub fn get_expires(&self) -> Option<SystemTime> {
+        self.attributes.iter().rev().find_map(|attr| match attr {
+            SetCookieAttribute::Expires(expires) => Some(*expires),
+            _ => None,
+        })
+    }
+
+    /// Gets the number of seconds until the cookie expires, as set by the last valid `Max-Age` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookie;
+    ///
+    /// let set_cookie = SetCookie::parse("a=b; Max-Age=3600").unwrap();
+    /// assert_eq!(Some(3600), set_cookie.get_max_age());
+    /// ```
+    pub fn get_max_age(&self) -> Option<i64> {
+        self.attributes.iter().rev().find_map(|attr| match attr {
+            SetCookieAttribute::MaxAge(max_age) => Some(*max_age),
+            _ => None,
+        })
+    }
+
+    /// Gets the domain set by the last valid `Domain` attribute,
+    /// converted to lower case and without a leading `.`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookie;
+    ///
+    /// let set_cookie = SetCookie::parse("a=b; Domain=.Example.COM").unwrap();
+    /// assert_eq!(Some("example.com"), set_cookie.get_domain());
+    /// ```
+    pub fn get_domain(&self) -> Option<&str> {
+        self.attributes.iter().rev().find_map(|attr| match attr {
+            SetCookieAttribute::Domain(domain) => Some(domain.as_ref()),
+            _ => None,
+        })
+    }
+
+    /// Gets the path set by the last `Path` attribute.
+    ///
+    /// Returns `None` if there is no such attribute, or if its value does not start with `/`,
+    /// in which case the default path of the request URI applies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookie;
+    ///
+    /// let set_cookie = SetCookie::parse("a=b; Path=/docs").unwrap();
+    /// assert_eq!(Some("/docs"), set_cookie.get_path());
+    /// ```
+    pub fn get_path(&self) -> Option<&'a str> {
+        self.attributes
+            .iter()
+            .rev()
+            .find_map(|attr| match attr {
+                SetCookieAttribute::Path(path) => Some(*path),
+                _ => None,
+            })
+            .filter(|path| path.starts_with('/'))
+    }
+
+    /// Whether the cookie has the `Secure` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookie;
+    ///
+    /// let set_cookie = SetCookie::parse("a=b; Secure").unwrap();
+    /// assert!(set_cookie.is_secure());
+    /// ```
+    pub fn is_secure(&self) -> bool {
+        self.attributes.contains(&SetCookieAttribute::Secure)
+    }
+
+    /// Whether the cookie has the `HttpOnly` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookie;
+    ///
+    /// let set_cookie = SetCookie::parse("a=b; HttpOnly").unwrap();
+    /// assert!(set_cookie.is_http_only());
+    /// ```
+    pub fn is_http_only(&self) -> bool {
+        self.attributes.contains(&SetCookieAttribute::HttpOnly)
+    }
+
+    /// Gets the policy set by the last valid `SameSite` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{SameSite, SetCookie};
+    ///
+    /// let set_cookie = SetCookie::parse("a=b; SameSite=strict").unwrap();
+    /// assert_eq!(Some(SameSite::Strict), set_cookie.get_same_site());
+    /// ```
+    pub fn get_same_site(&self) -> Option<SameSite> {
+        self.attributes.iter().rev().find_map(|attr| match attr {
+            SetCookieAttribute::SameSite(same_site) => Some(*same_site),
+            _ => None,
+        })
+    }
+
+    /// Gets the attributes which are not recognized, as `(name, value)` pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookie;
+    ///
+    /// let set_cookie = SetCookie::parse("a=b; Priority=High; Partitioned").unwrap();
+    /// assert_eq!(
+    ///     vec![("Priority", "High"), ("Partitioned", "")],
+    ///     set_cookie.get_unknown_attributes().collect::<Vec<_>>()
+    /// );
+    /// ```
+    pub fn get_unknown_attributes(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.attributes.iter().filter_map(|attr| match attr {
+            SetCookieAttribute::Unknown(name, value) => Some((*name, *value)),
+            _ => None,
+        })
+    }
+
+    /// Whether the name of the cookie starts with the `__Secure-` or `__Host-` prefix.
+    pub(crate) fn has_prefix(&self) -> bool {
+        has_name_prefix(self.name)
+    }
+
+    fn check_prefix(&self) -> Result<(), Error> {
+        check_name_prefix(
+            self.name,
+            self.is_secure(),
+            self.attributes
+                .iter()
+                .any(|attr| matches!(attr, SetCookieAttribute::Domain(_))),
+            self.get_path(),
+        )
+    }
+}
+
+/// Whether `name` starts with the `__Secure-` or `__Host-` prefix, ignoring case.
+pub(crate) fn has_name_prefix(name: &str) -> bool {
+    parsing::has_prefix(name, SECURE_PREFIX) || parsing::has_prefix(name, HOST_PREFIX)
+}
+
+/// Checks the requirements of the `__Secure-` and `__Host-` prefixes, as described in
+/// [RFC 6265bis](https://datatracker.ietf.org/doc/html/draft-ietf-httpbis-rfc6265bis#section-4.1.3).
+pub(crate) fn check_name_prefix(
+    name: &str,
+    secure: bool,
+    has_domain: bool,
+    path: Option<&str>,
+) -> Result<(), Error> {
+    if has_name_prefix(name) && !secure {
+        return Err(PrefixError::new(PrefixErrorKind::SecureRequired).into_error());
+    }
+
+    if parsing::has_prefix(name, HOST_PREFIX) {
+        if has_domain {
+            return Err(PrefixError::new(PrefixErrorKind::DomainNotAllowed).into_error());
+        }
+
+        if path != Some("/") {
+            return Err(PrefixError::new(PrefixErrorKind::RootPathRequired).into_error());
+        }
+    }
+
+    Ok(())
+}
+
+/// An attribute of a `Set-Cookie` header, as described in
+/// [RFC 6265, Section 5.2](https://tools.ietf.org/html/rfc6265.html#section-5.2).
+///
+/// With the `serde` feature, the `Expires` attribute is represented as whole seconds since the Unix epoch.
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SetCookieAttribute<'a> {
+    /// The `Expires` attribute.
+    Expires(#[cfg_attr(feature = "serde", serde(with = "crate::serde_time"))] SystemTime),
+    /// The `Max-Age` attribute in seconds. It is negative or zero for cookies that have already expired.
+    MaxAge(i64),
+    /// The `Domain` attribute, converted to lower case and without a leading `.`.
+    Domain(#[cfg_attr(feature = "serde", serde(borrow))] Cow<'a, str>),
+    /// The `Path` attribute, as it appears in the input.
+    Path(&'a str),
+    /// The `Secure` attribute.
+    Secure,
+    /// The `HttpOnly` attribute.
+    HttpOnly,
+    /// The `SameSite` attribute.
+    SameSite(SameSite),
+    /// An attribute that is not recognized, as a `(name, value)` pair.
+    Unknown(&'a str, &'a str),
+}
+
+impl<'a> SetCookieAttribute<'a> {
+    fn from_name_and_value(name: &'a str, value: &'a str) -> Option<SetCookieAttribute<'a>> {
+        if name.eq_ignore_ascii_case("expires") {
+            cookie_date::parse_cookie_date(value).map(SetCookieAttribute::Expires)
+        } else if name.eq_ignore_ascii_case("max-age") {
+            parsing::parse_delta_seconds(value).map(SetCookieAttribute::MaxAge)
+        } else if name.eq_ignore_ascii_case("domain") {
+            parsing::normalize_domain(value).map(SetCookieAttribute::Domain)
+        } else if name.eq_ignore_ascii_case("path") {
+            Some(SetCookieAttribute::Path(value))
+        } else if name.eq_ignore_ascii_case("secure") {
+            Some(SetCookieAttribute::Secure)
+        } else if name.eq_ignore_ascii_case("httponly") {
+            Some(SetCookieAttribute::HttpOnly)
+        } else if name.eq_ignore_ascii_case("samesite") {
+            SameSite::from_attribute_value(value).map(SetCookieAttribute::SameSite)
+        } else if name.is_empty() {
+            None
+        } else {
+            Some(SetCookieAttribute::Unknown(name, value))
+        }
+    }
+}
+
+/// The value of the `SameSite` attribute, as described in
+/// [RFC 6265bis](https://datatracker.ietf.org/doc/html/draft-ietf-httpbis-rfc6265bis#section-4.1.2.7).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SameSite {
+    /// `SameSite=Strict`
+    Strict,
+    /// `SameSite=Lax`
+    Lax,
+    /// `SameSite=None`
+    None,
+}
+
+impl SameSite {
+    fn from_attribute_value(value: &str) -> Option<SameSite> {
+        if value.eq_ignore_ascii_case("strict") {
+            Some(SameSite::Strict)
+        } else if value.eq_ignore_ascii_case("lax") {
+            Some(SameSite::Lax)
+        } else if value.eq_ignore_ascii_case("none") {
+            Some(SameSite::None)
+        } else {
+            None
+        }
+    }
+
+    /// Gets the attribute value for this policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SameSite;
+    ///
+    /// assert_eq!("Lax", SameSite::Lax.as_str());
+    /// ```
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+mod parsing {
+    use std::borrow::Cow;
+
+    /// Whether `name` starts with `prefix`, ignoring case.
+    pub fn has_prefix(name: &str, prefix: &str) -> bool {
+        name.as_bytes()
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix.as_bytes()))
+    }
+
+    /// Normalizes the value of a `Domain` attribute as described in
+    /// [RFC 6265, Section 5.2.3](https://tools.ietf.org/html/rfc6265.html#section-5.2.3).
+    pub fn normalize_domain(value: &str) -> Option<Cow<'_, str>> {
+        let domain = value.strip_prefix('.').unwrap_or(value);
+        if value.is_empty() {
+            None
+        } else if domain.bytes().any(|b| b.is_ascii_uppercase()) {
+            Some(Cow::Owned(domain.to_ascii_lowercase()))
+        } else {
+            Some(Cow::Borrowed(domain))
+        }
+    }
+
+    /// Parses the value of a `Max-Age` attribute as described in
+    /// [RFC 6265, Section 5.2.2](https://tools.ietf.org/html/rfc6265.html#section-5.2.2).
+    /// Values beyond the range of `i64` saturate.
+    pub fn parse_delta_seconds(value: &str) -> Option<i64> {
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, value),
+        };
+
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+
+        let mut result = 0_i64;
+        for digit in digits.bytes() {
+            let digit = i64::from(digit - b'0');
+            result = if negative {
+                result.saturating_mul(10).saturating_sub(digit)
+            } else {
+                result.saturating_mul(10).saturating_add(digit)
+            };
+        }
+
+        Some(result)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{normalize_domain, parse_delta_seconds};
+
+        #[test]
+        fn domain_leading_dot() {
+            assert_eq!(Some("example.com".into()), normalize_domain(".example.com"));
+        }
+
+        #[test]
+        fn domain_lower_case() {
+            assert_eq!(Some("example.com".into()), normalize_domain("eXample.COM"));
+        }
+
+        #[test]
+        fn domain_empty() {
+            assert_eq!(None, normalize_domain(""));
+        }
+
+        #[test]
+        fn positive() {
+            assert_eq!(Some(3600), parse_delta_seconds("3600"));
+        }
+
+        #[test]
+        fn negative() {
+            assert_eq!(Some(-1), parse_delta_seconds("-1"));
+        }
+
+        #[test]
+        fn saturates() {
+            assert_eq!(Some(i64::MAX), parse_delta_seconds("99999999999999999999"));
+            assert_eq!(Some(i64::MIN), parse_delta_seconds("-99999999999999999999"));
+        }
+
+        #[test]
+        fn invalid() {
+            assert_eq!(None, parse_delta_seconds(""));
+            assert_eq!(None, parse_delta_seconds("-"));
+            assert_eq!(None, parse_delta_seconds("+1"));
+            assert_eq!(None, parse_delta_seconds("12a"));
+        }
+    }
+}
+
+mod terminals {
+    use super::super::cookie::nonterminals::NonTerminalSpan;
+    use super::super::cookie::InternalError;
+    use super::super::Error;
+    use super::SetCookie as FullyParsedSetCookie;
+    use super::SetCookieAttribute;
+
+    #[derive(Debug)]
+    pub struct SetCookie {
+        pub(super) name: NonTerminalSpan,
+        pub(super) value: Option<NonTerminalSpan>,
+        pub(super) attributes: Vec<CookieAv>,
+    }
+
+    impl SetCookie {
+        pub(super) fn with_str<'a>(
+            &self,
+            data: &'a str,
+        ) -> Result<FullyParsedSetCookie<'a>, Error> {
+            let mut attributes = Vec::with_capacity(self.attributes.len());
+            for attribute in self.attributes.iter() {
+                if let Some(attribute) = attribute.with_str(data)? {
+                    attributes.push(attribute);
+                }
+            }
+
+            Ok(FullyParsedSetCookie {
+                name: self.name.as_str(data).map_err(InternalError::into_error)?,
+                value: optional_span_as_str(&self.value, data)?,
+                attributes,
+            })
+        }
+    }
+
+    #[derive(Debug)]
+    pub struct CookieAv {
+        pub(super) name: Option<NonTerminalSpan>,
+        pub(super) value: Option<Option<NonTerminalSpan>>,
+    }
+
+    impl CookieAv {
+        fn with_str<'a>(&self, data: &'a str) -> Result<Option<SetCookieAttribute<'a>>, Error> {
+            let name = optional_span_as_str(&self.name, data)?;
+            let value = match &self.value {
+                Some(value) => optional_span_as_str(value, data)?,
+                None => "",
+            };
+
+            Ok(SetCookieAttribute::from_name_and_value(name, value))
+        }
+    }
+
+    fn optional_span_as_str<'a>(
+        span: &Option<NonTerminalSpan>,
+        data: &'a str,
+    ) -> Result<&'a str, Error> {
+        match span {
+            Some(span) => span.as_str(data).map_err(InternalError::into_error),
+            None => Ok(""),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::cookie_date::system_time_from_unix_seconds;
+    use super::{SameSite, SetCookie, SetCookieAttribute};
+    use crate::{Error, PrefixErrorKind};
+
+    fn prefix_error_kind(input: &str) -> PrefixErrorKind {
+        match SetCookie::parse(input) {
+            Err(Error::PrefixError(err)) => err.kind(),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn name_and_value() {
+        const SET_COOKIE_STR: &str = "test=1234";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!("test", set_cookie.name);
+        assert_eq!("1234", set_cookie.value);
+        assert!(set_cookie.attributes.is_empty());
+    }
+
+    #[test]
+    fn empty_value() {
+        const SET_COOKIE_STR: &str = "test=";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!("test", set_cookie.name);
+        assert_eq!("", set_cookie.value);
+    }
+
+    #[test]
+    fn quoted_value_is_kept_verbatim() {
+        const SET_COOKIE_STR: &str = "test=\"quoted\"";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!("test", set_cookie.name);
+        assert_eq!("\"quoted\"", set_cookie.value);
+    }
+
+    #[test]
+    fn value_with_equals_and_spaces() {
+        const SET_COOKIE_STR: &str = "  test = abc=1 23  ; Secure";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!("test", set_cookie.name);
+        assert_eq!("abc=1 23", set_cookie.value);
+        assert_eq!(vec![SetCookieAttribute::Secure], set_cookie.attributes);
+    }
+
+    #[test]
+    fn all_attributes() {
+        const SET_COOKIE_STR: &str = "id=a3fWa; Expires=Wed, 21 Oct 2015 07:28:00 GMT; \
+            Max-Age=2592000; Domain=example.com; Path=/docs; Secure; HttpOnly; SameSite=Lax";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!("id", set_cookie.name);
+        assert_eq!("a3fWa", set_cookie.value);
+        assert_eq!(
+            vec![
+                SetCookieAttribute::Expires(system_time_from_unix_seconds(1445412480)),
+                SetCookieAttribute::MaxAge(2592000),
+                SetCookieAttribute::Domain("example.com".into()),
+                SetCookieAttribute::Path("/docs"),
+                SetCookieAttribute::Secure,
+                SetCookieAttribute::HttpOnly,
+                SetCookieAttribute::SameSite(SameSite::Lax),
+            ],
+            set_cookie.attributes
+        );
+    }
+
+    #[test]
+    fn attribute_names_are_case_insensitive() {
+        const SET_COOKIE_STR: &str = "a=b; SECURE; httponly; max-AGE=10";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!(
+            vec![
+                SetCookieAttribute::Secure,
+                SetCookieAttribute::HttpOnly,
+                SetCookieAttribute::MaxAge(10),
+            ],
+            set_cookie.attributes
+        );
+    }
+
+    #[test]
+    fn attributes_with_whitespace() {
+        const SET_COOKIE_STR: &str = "a=b ;Path = /x \x09;  Domain=  example.com";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!(
+            vec![
+                SetCookieAttribute::Path("/x"),
+                SetCookieAttribute::Domain("example.com".into()),
+            ],
+            set_cookie.attributes
+        );
+    }
+
+    #[test]
+    fn invalid_attributes_are_ignored() {
+        const SET_COOKIE_STR: &str =
+            "a=b; Max-Age=soon; Domain=; Expires=tomorrow; SameSite=Sometimes; =x; ;";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert!(set_cookie.attributes.is_empty());
+    }
+
+    #[test]
+    fn unknown_attributes_are_kept() {
+        const SET_COOKIE_STR: &str = "a=b; Foo=bar; Partitioned";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!(
+            vec![
+                SetCookieAttribute::Unknown("Foo", "bar"),
+                SetCookieAttribute::Unknown("Partitioned", ""),
+            ],
+            set_cookie.attributes
+        );
+    }
+
+    #[test]
+    fn last_attribute_wins() {
+        const SET_COOKIE_STR: &str = "a=b; Max-Age=10; Domain=a.com; Max-Age=20; \
+            Domain=B.com; SameSite=Strict; SameSite=None";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!(Some(20), set_cookie.get_max_age());
+        assert_eq!(Some("b.com"), set_cookie.get_domain());
+        assert_eq!(Some(SameSite::None), set_cookie.get_same_site());
+    }
+
+    #[test]
+    fn path_without_leading_slash() {
+        const SET_COOKIE_STR: &str = "a=b; Path=/docs; Path=docs";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!(None, set_cookie.get_path());
+    }
+
+    #[test]
+    fn no_attributes() {
+        const SET_COOKIE_STR: &str = "a=b";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!(None, set_cookie.get_expires());
+        assert_eq!(None, set_cookie.get_max_age());
+        assert_eq!(None, set_cookie.get_domain());
+        assert_eq!(None, set_cookie.get_path());
+        assert!(!set_cookie.is_secure());
+        assert!(!set_cookie.is_http_only());
+        assert_eq!(None, set_cookie.get_same_site());
+    }
+
+    #[test]
+    fn trailing_semicolon() {
+        const SET_COOKIE_STR: &str = "a=b;";
+        let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+        assert_eq!("a", set_cookie.name);
+        assert_eq!("b", set_cookie.value);
+    }
+
+    #[test]
+    fn non_ascii() {
+        let set_cookie =
+            SetCookie::parse("a=caf\u{e9}; Path=/caf\u{e9}; Domain=\u{e9}.example").unwrap();
+
+        assert_eq!("caf\u{e9}", set_cookie.get_value());
+        assert_eq!(Some("/caf\u{e9}"), set_cookie.get_path());
+        assert_eq!(Some("\u{e9}.example"), set_cookie.get_domain());
+        assert_eq!(
+            "caf\u{e9}",
+            SetCookie::parse("caf\u{e9}=1").unwrap().get_name()
+        );
+    }
+
+    #[test]
+    fn control_characters() {
+        assert!(SetCookie::parse("a=b\x01c").is_err());
+        assert!(SetCookie::parse("a=b\x7fc").is_err());
+        assert!(SetCookie::parse("a=b; Path=/\x00").is_err());
+    }
+
+    #[test]
+    fn missing_equals() {
+        assert!(SetCookie::parse("abc; Secure").is_err());
+    }
+
+    #[test]
+    fn empty_name() {
+        assert!(SetCookie::parse("=abc").is_err());
+    }
+
+    #[test]
+    fn empty() {
+        assert!(SetCookie::parse("").is_err());
+    }
+
+    #[test]
+    fn secure_prefix() {
+        assert!(SetCookie::parse("__Secure-id=1; Secure").is_ok());
+        assert!(SetCookie::parse("__Secure-id=1; Secure; Domain=example.com; Path=/docs").is_ok());
+        assert_eq!(
+            PrefixErrorKind::SecureRequired,
+            prefix_error_kind("__Secure-id=1; Path=/")
+        );
+    }
+
+    #[test]
+    fn host_prefix() {
+        assert!(SetCookie::parse("__Host-id=1; Secure; Path=/").is_ok());
+        assert_eq!(
+            PrefixErrorKind::SecureRequired,
+            prefix_error_kind("__Host-id=1; Path=/")
+        );
+        assert_eq!(
+            PrefixErrorKind::DomainNotAllowed,
+            prefix_error_kind("__Host-id=1; Secure; Path=/; Domain=example.com")
+        );
+        assert_eq!(
+            PrefixErrorKind::RootPathRequired,
+            prefix_error_kind("__Host-id=1; Secure")
+        );
+        assert_eq!(
+            PrefixErrorKind::RootPathRequired,
+            prefix_error_kind("__Host-id=1; Secure; Path=/; Path=/docs")
+        );
+    }
+
+    #[test]
+    fn prefix_case_insensitive() {
+        assert_eq!(
+            PrefixErrorKind::SecureRequired,
+            prefix_error_kind("__SECURE-id=1")
+        );
+        assert_eq!(
+            PrefixErrorKind::RootPathRequired,
+            prefix_error_kind("__host-id=1; Secure")
+        );
+    }
+
+    #[test]
+    fn not_a_prefix() {
+        assert!(SetCookie::parse("__Secure=1").is_ok());
+        assert!(SetCookie::parse("__Host=1").is_ok());
+        assert!(SetCookie::parse("Secure-id=1").is_ok());
+        assert!(SetCookie::parse("_Host-id=1").is_ok());
+    }
+
+    #[cfg(feature = "serde")]
+    mod serde {
+        use super::super::cookie_date::system_time_from_unix_seconds;
+        use super::{SameSite, SetCookie, SetCookieAttribute};
+        use std::borrow::Cow;
+
+        const SET_COOKIE_STR: &str =
+            "id=a3fWa; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Domain=Example.com; SameSite=Lax";
+        const JSON: &str = concat!(
+            r#"{"name":"id","value":"a3fWa","attributes":["#,
+            r#"{"Expires":1445412480},{"Domain":"example.com"},{"SameSite":"Lax"}]}"#
+        );
+
+        #[test]
+        fn serialize() {
+            let set_cookie = SetCookie::parse(SET_COOKIE_STR).unwrap();
+
+            assert_eq!(JSON, serde_json::to_string(&set_cookie).unwrap());
+        }
+
+        #[test]
+        fn deserialize_borrowed() {
+            let set_cookie: SetCookie = serde_json::from_str(JSON).unwrap();
+
+            assert_eq!("id", set_cookie.get_name());
+            assert_eq!(
+                vec![
+                    SetCookieAttribute::Expires(system_time_from_unix_seconds(1_445_412_480)),
+                    SetCookieAttribute::Domain(Cow::Borrowed("example.com")),
+                    SetCookieAttribute::SameSite(SameSite::Lax),
+                ],
+                set_cookie.attributes
+            );
+            assert!(matches!(
+                set_cookie.attributes[1],
+                SetCookieAttribute::Domain(Cow::Borrowed(_))
+            ));
+        }
+
+        #[test]
+        fn expires_before_epoch() {
+            let set_cookie =
+                SetCookie::parse("a=1; Expires=Thu, 01 Jan 1970 00:00:00 GMT").unwrap();
+            let json = serde_json::to_string(&set_cookie).unwrap();
+
+            assert!(json.contains(r#"{"Expires":0}"#), "json: {}", json);
+            assert!(serde_json::from_str::<SetCookie>(
+                r#"{"name":"a","value":"1","attributes":[{"Expires":-1}]}"#
+            )
+            .is_ok());
+        }
+    }
+}
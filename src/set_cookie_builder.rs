@@ -0,0 +1,433 @@
+use super::cookie::{SerializeError, SerializeErrorKind};
+use super::{cookie_date, cookie_serializer, set_cookie, Error, SameSite};
+use std::time::SystemTime;
+
+/// Builds the value of a `Set-Cookie` response header, as described in
+/// [RFC 6265, Section 4.1](https://tools.ietf.org/html/rfc6265.html#section-4.1).
+///
+/// The name, the value and the attributes are validated when the header is built,
+/// so that the result can always be parsed by [`SetCookie::parse`](crate::SetCookie::parse).
+#[derive(Clone, Debug)]
+pub struct SetCookieBuilder<'a> {
+    name: &'a str,
+    value: &'a str,
+    expires: Option<SystemTime>,
+    max_age: Option<i64>,
+    domain: Option<&'a str>,
+    path: Option<&'a str>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+}
+
+impl<'a> SetCookieBuilder<'a> {
+    /// Creates a builder for a cookie with the given name and value, and no attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookieBuilder;
+    ///
+    /// let header = SetCookieBuilder::new("name", "value").build().unwrap();
+    /// assert_eq!("name=value", header);
+    /// ```
+    pub fn new(name: &'a str, value: &'a str) -> SetCookieBuilder<'a> {
+        SetCookieBuilder {
+            name,
+            value,
+            expires: None,
+            max_age: None,
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+        }
+    }
+
+    /// Sets the `Expires` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookieBuilder;
+    /// use std::time::{Duration, UNIX_EPOCH};
+    ///
+    /// let header = SetCookieBuilder::new("name", "value")
+    ///     .expires(UNIX_EPOCH + Duration::from_secs(784111777))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!("name=value; Expires=Sun, 06 Nov 1994 08:49:37 GMT", header);
+    /// ```
+    pub fn expires(mut self, expires: SystemTime) -> SetCookieBuilder<'a> {
+        self.expires = Some(expires);
+        self
+    }
+
+    /// Sets the `Max-Age` attribute, in seconds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookieBuilder;
+    ///
+    /// let header = SetCookieBuilder::new("name", "value").max_age(3600).build().unwrap();
+    /// assert_eq!("name=value; Max-Age=3600", header);
+    /// ```
+    pub fn max_age(mut self, max_age: i64) -> SetCookieBuilder<'a> {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Sets the `Domain` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookieBuilder;
+    ///
+    /// let header = SetCookieBuilder::new("name", "value")
+    ///     .domain("example.com")
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!("name=value; Domain=example.com", header);
+    /// ```
+    pub fn domain(mut self, domain: &'a str) -> SetCookieBuilder<'a> {
+        self.domain = Some(domain);
+        self
+    }
+
+    /// Sets the `Path` attribute. The path must start with `/`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookieBuilder;
+    ///
+    /// let header = SetCookieBuilder::new("name", "value").path("/docs").build().unwrap();
+    /// assert_eq!("name=value; Path=/docs", header);
+    /// ```
+    pub fn path(mut self, path: &'a str) -> SetCookieBuilder<'a> {
+        self.path = Some(path);
+        self
+    }
+
+    /// Sets whether the cookie has the `Secure` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookieBuilder;
+    ///
+    /// let header = SetCookieBuilder::new("name", "value").secure(true).build().unwrap();
+    /// assert_eq!("name=value; Secure", header);
+    /// ```
+    pub fn secure(mut self, secure: bool) -> SetCookieBuilder<'a> {
+        self.secure = secure;
+        self
+    }
+
+    /// Sets whether the cookie has the `HttpOnly` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::SetCookieBuilder;
+    ///
+    /// let header = SetCookieBuilder::new("name", "value").http_only(true).build().unwrap();
+    /// assert_eq!("name=value; HttpOnly", header);
+    /// ```
+    pub fn http_only(mut self, http_only: bool) -> SetCookieBuilder<'a> {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Sets the `SameSite` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{SameSite, SetCookieBuilder};
+    ///
+    /// let header = SetCookieBuilder::new("name", "value")
+    ///     .same_site(SameSite::Strict)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!("name=value; SameSite=Strict", header);
+    /// ```
+    pub fn same_site(mut self, same_site: SameSite) -> SetCookieBuilder<'a> {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Builds the value of the `Set-Cookie` header.
+    ///
+    /// Returns an error if the name is not a valid non-empty token, the value is not a valid
+    /// cookie value, the domain or the path contain characters which are not allowed in an
+    /// attribute value, the path does not start with `/`, or the expiry date cannot be represented.
+    /// Names with the `__Secure-` or `__Host-` prefix must also meet the requirements of the prefix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use basic_cookies::{SameSite, SetCookie, SetCookieBuilder};
+    ///
+    /// let header = SetCookieBuilder::new("id", "a3fWa")
+    ///     .max_age(2592000)
+    ///     .path("/")
+    ///     .secure(true)
+    ///     .http_only(true)
+    ///     .same_site(SameSite::Lax)
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     "id=a3fWa; Max-Age=2592000; Path=/; Secure; HttpOnly; SameSite=Lax",
+    ///     header
+    /// );
+    ///
+    /// let set_cookie = SetCookie::parse(&header).unwrap();
+    /// assert_eq!("id", set_cookie.get_name());
+    /// assert_eq!(Some(2592000), set_cookie.get_max_age());
+    /// ```
+    pub fn build(&self) -> Result<String, Error> {
+        if self.name.is_empty() {
+            return Err(SerializeError::new(SerializeErrorKind::Name).into_error());
+        }
+
+        set_cookie::check_name_prefix(self.name, self.secure, self.domain.is_some(), self.path)?;
+
+        let mut output = String::new();
+
+        match self
+            .value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+        {
+            Some(unquoted_value) if !unquoted_value.is_empty() => {
+                cookie_serializer::write_cookie_pair(&mut output, self.name, unquoted_value, true)?
+            }
+            _ => cookie_serializer::write_cookie_pair(&mut output, self.name, self.value, false)?,
+        }
+
+        if let Some(expires) = self.expires {
+            let expires = cookie_date::format_imf_fixdate(expires)
+                .ok_or_else(|| SerializeError::new(SerializeErrorKind::Expires).into_error())?;
+            output.push_str("; Expires=");
+            output.push_str(&expires);
+        }
+
+        if let Some(max_age) = self.max_age {
+            output.push_str("; Max-Age=");
+            output.push_str(&max_age.to_string());
+        }
+
+        if let Some(domain) = self.domain {
+            write_attribute_value(&mut output, "Domain", domain)?;
+        }
+
+        if let Some(path) = self.path {
+            if !path.starts_with('/') {
+                return Err(SerializeError::new(SerializeErrorKind::AttributeValue).into_error());
+            }
+            write_attribute_value(&mut output, "Path", path)?;
+        }
+
+        if self.secure {
+            output.push_str("; Secure");
+        }
+
+        if self.http_only {
+            output.push_str("; HttpOnly");
+        }
+
+        if let Some(same_site) = self.same_site {
+            output.push_str("; SameSite=");
+            output.push_str(same_site.as_str());
+        }
+
+        Ok(output)
+    }
+}
+
+/// Writes `; name=value`, if `value` is a non-empty `av-octet` string
+/// without leading or trailing whitespace.
+fn write_attribute_value(output: &mut String, name: &str, value: &str) -> Result<(), Error> {
+    let is_valid = !value.is_empty()
+        && value
+            .bytes()
+            .all(|b| matches!(b, b'\x20'..=b'\x7e') && b != b';')
+        && !value.starts_with(' ')
+        && !value.ends_with(' ');
+
+    if !is_valid {
+        return Err(SerializeError::new(SerializeErrorKind::AttributeValue).into_error());
+    }
+
+    output.push_str("; ");
+    output.push_str(name);
+    output.push('=');
+    output.push_str(value);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SetCookieBuilder;
+    use crate::cookie_date::system_time_from_unix_seconds;
+    use crate::{Error, SameSite, SerializeErrorKind, SetCookie};
+
+    fn serialize_error_kind(builder: SetCookieBuilder) -> SerializeErrorKind {
+        match builder.build() {
+            Err(Error::SerializeError(err)) => err.kind(),
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn all_attributes() {
+        let header = SetCookieBuilder::new("id", "a3fWa")
+            .expires(system_time_from_unix_seconds(1445412480))
+            .max_age(-1)
+            .domain("example.com")
+            .path("/docs")
+            .secure(true)
+            .http_only(true)
+            .same_site(SameSite::None)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "id=a3fWa; Expires=Wed, 21 Oct 2015 07:28:00 GMT; Max-Age=-1; \
+             Domain=example.com; Path=/docs; Secure; HttpOnly; SameSite=None",
+            header
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let expires = system_time_from_unix_seconds(1445412480);
+        let header = SetCookieBuilder::new("id", "a3fWa")
+            .expires(expires)
+            .max_age(60)
+            .domain("Example.com")
+            .path("/a b")
+            .secure(true)
+            .http_only(true)
+            .same_site(SameSite::Strict)
+            .build()
+            .unwrap();
+
+        let set_cookie = SetCookie::parse(&header).unwrap();
+        assert_eq!("id", set_cookie.get_name());
+        assert_eq!("a3fWa", set_cookie.get_value());
+        assert_eq!(Some(expires), set_cookie.get_expires());
+        assert_eq!(Some(60), set_cookie.get_max_age());
+        assert_eq!(Some("example.com"), set_cookie.get_domain());
+        assert_eq!(Some("/a b"), set_cookie.get_path());
+        assert!(set_cookie.is_secure());
+        assert!(set_cookie.is_http_only());
+        assert_eq!(Some(SameSite::Strict), set_cookie.get_same_site());
+    }
+
+    #[test]
+    fn quoted_value() {
+        let header = SetCookieBuilder::new("a", "\"b=c\"").build().unwrap();
+
+        assert_eq!("a=\"b=c\"", header);
+        assert_eq!("\"b=c\"", SetCookie::parse(&header).unwrap().get_value());
+    }
+
+    #[test]
+    fn empty_value() {
+        assert_eq!("a=", SetCookieBuilder::new("a", "").build().unwrap());
+    }
+
+    #[test]
+    fn invalid_name() {
+        assert!(SetCookieBuilder::new("", "b").build().is_err());
+        assert!(SetCookieBuilder::new("a b", "c").build().is_err());
+        assert!(SetCookieBuilder::new("a;", "c").build().is_err());
+    }
+
+    #[test]
+    fn invalid_value() {
+        assert!(SetCookieBuilder::new("a", "b c").build().is_err());
+        assert!(SetCookieBuilder::new("a", "b;c").build().is_err());
+        assert!(SetCookieBuilder::new("a", "\"b").build().is_err());
+        assert!(SetCookieBuilder::new("a", "\"\"").build().is_err());
+    }
+
+    #[test]
+    fn invalid_domain() {
+        assert!(SetCookieBuilder::new("a", "b").domain("").build().is_err());
+        assert!(SetCookieBuilder::new("a", "b")
+            .domain("a.com; Secure")
+            .build()
+            .is_err());
+        assert!(SetCookieBuilder::new("a", "b")
+            .domain(" a.com")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn invalid_path() {
+        assert!(SetCookieBuilder::new("a", "b")
+            .path("docs")
+            .build()
+            .is_err());
+        assert!(SetCookieBuilder::new("a", "b").path("/\n").build().is_err());
+    }
+
+    #[test]
+    fn invalid_expires() {
+        assert!(SetCookieBuilder::new("a", "b")
+            .expires(system_time_from_unix_seconds(-11_644_473_601))
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn error_kinds() {
+        assert_eq!(
+            SerializeErrorKind::Name,
+            serialize_error_kind(SetCookieBuilder::new("a b", "c"))
+        );
+        assert_eq!(
+            SerializeErrorKind::Value,
+            serialize_error_kind(SetCookieBuilder::new("a", "b c"))
+        );
+        assert_eq!(
+            SerializeErrorKind::AttributeValue,
+            serialize_error_kind(SetCookieBuilder::new("a", "b").path("docs"))
+        );
+        assert_eq!(
+            SerializeErrorKind::Expires,
+            serialize_error_kind(
+                SetCookieBuilder::new("a", "b")
+                    .expires(system_time_from_unix_seconds(-11_644_473_601))
+            )
+        );
+    }
+
+    #[test]
+    fn prefixes() {
+        assert_eq!(
+            "__Host-id=1; Path=/; Secure",
+            SetCookieBuilder::new("__Host-id", "1")
+                .path("/")
+                .secure(true)
+                .build()
+                .unwrap()
+        );
+        assert!(SetCookieBuilder::new("__Secure-id", "1").build().is_err());
+        assert!(SetCookieBuilder::new("__Host-id", "1")
+            .secure(true)
+            .path("/")
+            .domain("example.com")
+            .build()
+            .is_err());
+    }
+}